@@ -60,7 +60,7 @@ pub fn derive_scenic_partial(input: TokenStream) -> TokenStream {
             }
             fn implement_scene(&mut self,
                                settings: &brainworms_lib::theater::basement::cla::GameProgrammeSettings,
-                               event_loop: &brainworms_lib::winit::event_loop::EventLoop<brainworms_lib::MyEvent>,
+                               event_loop_proxy: &brainworms_lib::winit::event_loop::EventLoopProxy<brainworms_lib::MyEvent>,
                                renderer: std::sync::Arc<brainworms_lib::rend3::Renderer>,
                                routines: std::sync::Arc<brainworms_lib::theater::play::backstage::plumbing::DefaultRoutines>,
                                rts: &brainworms_lib::tokio::runtime::Runtime,
@@ -69,7 +69,7 @@ pub fn derive_scenic_partial(input: TokenStream) -> TokenStream {
             ) {
                 self.implement(
                     settings,
-                    event_loop,
+                    event_loop_proxy,
                     renderer,
                     routines,
                     rts,
@@ -85,10 +85,23 @@ pub fn derive_scenic_partial(input: TokenStream) -> TokenStream {
 
     tokens.into()
 }
-#[proc_macro_derive(Choral, attributes(user_data_struct))]
+#[proc_macro_derive(Choral, attributes(input_context_enum, user_data_struct))]
 pub fn derive_choral(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let ident = input.ident;
+    let the_input_context_enum: &Ident = &input
+        .attrs
+        .iter()
+        .find(|a| {
+            a.path()
+                .get_ident()
+                .is_some_and(|aa| aa == "input_context_enum")
+        })
+        .map(|aa| {
+            aa.parse_args()
+                .expect("input_context_enum attribute required")
+        })
+        .expect("input_context_enum attribute required");
     let the_user_data_struct: &Ident = &input
         .attrs
         .iter()
@@ -103,9 +116,9 @@ pub fn derive_choral(input: TokenStream) -> TokenStream {
         })
         .expect("user_data_struct attribute required");
     let tokens = quote! {
-        impl brainworms_lib::theater::play::scene::chorus::Choral<#the_user_data_struct> for #ident {
-            fn implement_chorus_for_choral(&self, egui_ctx: brainworms_lib::egui::Context, orchestra: std::sync::Arc<brainworms_lib::theater::play::orchestra::Orchestra>,settings: &brainworms_lib::theater::basement::cla::GameProgrammeSettings, user_data: std::sync::Arc<brainworms_lib::parking_lot::Mutex<#the_user_data_struct>>) {
-                self.implement_chorus(egui_ctx, orchestra,settings,user_data);
+        impl brainworms_lib::theater::play::scene::chorus::Choral<#the_input_context_enum, #the_user_data_struct> for #ident {
+            fn implement_chorus_for_choral(&self, egui_ctx: brainworms_lib::egui::Context, orchestra: std::sync::Arc<brainworms_lib::theater::play::orchestra::Orchestra>,settings: &brainworms_lib::theater::basement::cla::GameProgrammeSettings, state: &mut brainworms_lib::GameProgrammeState<#the_input_context_enum>, user_data: std::sync::Arc<brainworms_lib::parking_lot::Mutex<#the_user_data_struct>>) {
+                self.implement_chorus(egui_ctx, orchestra,settings,state,user_data);
             }
             fn chorus_uuid(&self) -> brainworms_lib::uuid::Uuid {
                 self.uuid
@@ -190,27 +203,47 @@ pub fn derive_playable(input: TokenStream) -> TokenStream {
 
                 fn implement_playable(&mut self,
                                       settings: &brainworms_lib::theater::basement::cla::GameProgrammeSettings,
-                                      event_loop: &brainworms_lib::winit::event_loop::EventLoop<brainworms_lib::MyEvent>,
+                                      event_loop_proxy: &brainworms_lib::winit::event_loop::EventLoopProxy<brainworms_lib::MyEvent>,
                                       renderer:std::sync::Arc<brainworms_lib::rend3::Renderer>,
                                       routines:std::sync::Arc<brainworms_lib::theater::play::backstage::plumbing::DefaultRoutines>,
                                       rts: &brainworms_lib::tokio::runtime::Runtime,
                                       orchestra:std::sync::Arc<brainworms_lib::theater::play::orchestra::Orchestra>,
                                       user_data: std::sync::Arc<brainworms_lib::parking_lot::Mutex<#the_user_data_struct>>
                 ) {
-
+                    self.implement(settings, event_loop_proxy, renderer, routines, rts, orchestra, user_data)
                 }
 
                 fn define_playable(&mut self) {
                     self.define_chorus()
                 }
 
-                fn implement_chorus_for_playable(&self,egui_ctx:brainworms_lib::egui::Context,orchestra:std::sync::Arc<brainworms_lib::theater::play::orchestra::Orchestra>,      settings: &brainworms_lib::theater::basement::cla::GameProgrammeSettings,                                user_data: std::sync::Arc<brainworms_lib::parking_lot::Mutex<#the_user_data_struct>>) {
-                    self.implement_chorus_for_choral(egui_ctx,orchestra,settings,user_data)
+                fn implement_chorus_for_playable(&self,egui_ctx:brainworms_lib::egui::Context,orchestra:std::sync::Arc<brainworms_lib::theater::play::orchestra::Orchestra>,      settings: &brainworms_lib::theater::basement::cla::GameProgrammeSettings, state: &mut brainworms_lib::GameProgrammeState<#the_input_context_enum>, user_data: std::sync::Arc<brainworms_lib::parking_lot::Mutex<#the_user_data_struct>>) {
+                    self.implement_chorus_for_choral(egui_ctx,orchestra,settings,state,user_data)
                 }
 
-                fn handle_input_for_playable(&mut self,settings: &brainworms_lib::theater::basement::cla::GameProgrammeSettings,state: &mut brainworms_lib::GameProgrammeState<#the_input_context_enum>,window: &std::sync::Arc<brainworms_lib::winit::window::Window>,) {
+                fn handle_input_for_playable(&mut self,settings: &brainworms_lib::theater::basement::cla::GameProgrammeSettings,state: &mut brainworms_lib::GameProgrammeState<#the_input_context_enum>,window: Option<&std::sync::Arc<brainworms_lib::winit::window::Window>>,) {
                     // egui has its own input handling
                 }
+
+                fn playable_on_enter(&mut self, state: &mut brainworms_lib::GameProgrammeState<#the_input_context_enum>) {
+                    brainworms_lib::theater::play::Cued::on_enter(self, state)
+                }
+
+                fn playable_on_exit(&mut self, state: &mut brainworms_lib::GameProgrammeState<#the_input_context_enum>) {
+                    brainworms_lib::theater::play::Cued::on_exit(self, state)
+                }
+
+                fn playable_on_camera_arrived(&mut self, state: &mut brainworms_lib::GameProgrammeState<#the_input_context_enum>, camera: &str) {
+                    brainworms_lib::theater::play::Cued::on_camera_arrived(self, state, camera)
+                }
+
+                fn playable_on_interaction(&mut self, state: &mut brainworms_lib::GameProgrammeState<#the_input_context_enum>, callback: &str, collider: &str) {
+                    brainworms_lib::theater::play::Cued::on_interaction(self, state, callback, collider)
+                }
+
+                fn playable_reads_file(&self, path: &std::path::Path) -> bool {
+                    brainworms_lib::theater::play::Cued::<#the_input_context_enum>::reads_file(self, path)
+                }
             }
 
                         }
@@ -240,15 +273,20 @@ pub fn derive_playable(input: TokenStream) -> TokenStream {
             let def_pl = imp_fn("define_playable", "");
             let imp_pl = imp_fn(
                 "implement_playable",
-                "settings,event_loop,renderer,routines,rts,orchestra,user_data",
+                "settings,event_loop_proxy,renderer,routines,rts,orchestra,user_data",
             );
             let imp_chr = imp_fn(
                 "implement_chorus_for_playable",
-                "egui_ctx,orchestra,settings,user_data",
+                "egui_ctx,orchestra,settings,state,user_data",
             );
             let pl_def = imp_fn("playable_definition", "");
             let pl_imp = imp_fn("playable_implementation", "");
             let pl_inp = imp_fn("handle_input_for_playable", "settings,state,window");
+            let pl_enter = imp_fn("playable_on_enter", "state");
+            let pl_exit = imp_fn("playable_on_exit", "state");
+            let pl_arrived = imp_fn("playable_on_camera_arrived", "state,camera");
+            let pl_interaction = imp_fn("playable_on_interaction", "state,callback,collider");
+            let pl_reads = imp_fn("playable_reads_file", "path");
             quote! {
             impl brainworms_lib::theater::play::Playable<#the_input_context_enum, #the_user_data_struct> for #ident {
                 fn playable_uuid(&self) -> brainworms_lib::uuid::Uuid {
@@ -267,7 +305,7 @@ pub fn derive_playable(input: TokenStream) -> TokenStream {
                     }
                 }
                 fn implement_playable(&mut self,settings: &brainworms_lib::theater::basement::cla::GameProgrammeSettings,
-                                      event_loop: &brainworms_lib::winit::event_loop::EventLoop<brainworms_lib::MyEvent>,
+                                      event_loop_proxy: &brainworms_lib::winit::event_loop::EventLoopProxy<brainworms_lib::MyEvent>,
                                       renderer:std::sync::Arc<brainworms_lib::rend3::Renderer>,
                                       routines:std::sync::Arc<brainworms_lib::theater::play::backstage::plumbing::DefaultRoutines>,
                                       rts: &brainworms_lib::tokio::runtime::Runtime,
@@ -284,7 +322,7 @@ pub fn derive_playable(input: TokenStream) -> TokenStream {
                         #(#def_pl),*
                     }
                 }
-                fn implement_chorus_for_playable(&self, egui_ctx: brainworms_lib::egui::Context, orchestra: std::sync::Arc<brainworms_lib::theater::play::orchestra::Orchestra>,settings: &brainworms_lib::theater::basement::cla::GameProgrammeSettings,user_data: std::sync::Arc<brainworms_lib::parking_lot::Mutex<#the_user_data_struct>>) {
+                fn implement_chorus_for_playable(&self, egui_ctx: brainworms_lib::egui::Context, orchestra: std::sync::Arc<brainworms_lib::theater::play::orchestra::Orchestra>,settings: &brainworms_lib::theater::basement::cla::GameProgrammeSettings,state: &mut brainworms_lib::GameProgrammeState<#the_input_context_enum>,user_data: std::sync::Arc<brainworms_lib::parking_lot::Mutex<#the_user_data_struct>>) {
                     match self {
                         #(#imp_chr),*
                     }
@@ -299,11 +337,36 @@ pub fn derive_playable(input: TokenStream) -> TokenStream {
                         #(#pl_imp),*
                     }
                 }
-                fn handle_input_for_playable(&mut self,settings: &brainworms_lib::theater::basement::cla::GameProgrammeSettings,state: &mut brainworms_lib::GameProgrammeState<#the_input_context_enum>,window: &std::sync::Arc<brainworms_lib::winit::window::Window>) {
+                fn handle_input_for_playable(&mut self,settings: &brainworms_lib::theater::basement::cla::GameProgrammeSettings,state: &mut brainworms_lib::GameProgrammeState<#the_input_context_enum>,window: Option<&std::sync::Arc<brainworms_lib::winit::window::Window>>) {
                     match self {
                         #(#pl_inp),*
                     }
                 }
+                fn playable_on_enter(&mut self, state: &mut brainworms_lib::GameProgrammeState<#the_input_context_enum>) {
+                    match self {
+                        #(#pl_enter),*
+                    }
+                }
+                fn playable_on_exit(&mut self, state: &mut brainworms_lib::GameProgrammeState<#the_input_context_enum>) {
+                    match self {
+                        #(#pl_exit),*
+                    }
+                }
+                fn playable_on_camera_arrived(&mut self, state: &mut brainworms_lib::GameProgrammeState<#the_input_context_enum>, camera: &str) {
+                    match self {
+                        #(#pl_arrived),*
+                    }
+                }
+                fn playable_on_interaction(&mut self, state: &mut brainworms_lib::GameProgrammeState<#the_input_context_enum>, callback: &str, collider: &str) {
+                    match self {
+                        #(#pl_interaction),*
+                    }
+                }
+                fn playable_reads_file(&self, path: &std::path::Path) -> bool {
+                    match self {
+                        #(#pl_reads),*
+                    }
+                }
             }}
         }
         syn::Data::Union(_) => {
@@ -0,0 +1,103 @@
+// picking on made up stages full of boxes, the Qbvh against asking every collider like it used to
+use std::collections::HashMap;
+
+use brainworms_lib::{
+    nalgebra::{Point3, Vector3},
+    parry3d::{
+        query::{Ray, RayCast},
+        shape::{Cuboid, TriMesh},
+    },
+    theater::play::scene::stage3d::{picking::PickFilter, ColliderMap, Colliders},
+};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+const STAGE_SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+/// `boxes` colliders of a box each, on a square floor plan two units apart.
+fn synthetic_stage(boxes: usize) -> ColliderMap {
+    let side = (boxes as f32).sqrt().ceil() as usize;
+    let (vertices, indices) = Cuboid::new(Vector3::new(0.5, 0.5, 0.5)).to_trimesh();
+    (0..boxes)
+        .map(|i| {
+            let offset = Vector3::new((i % side) as f32 * 2.0, 0.0, (i / side) as f32 * 2.0);
+            let vertices = vertices.iter().map(|v| v + offset).collect();
+            (
+                format!("box{i}"),
+                vec![TriMesh::new(vertices, indices.clone())],
+            )
+        })
+        .collect()
+}
+
+/// Rays from above the middle of the stage, fanning out over it.
+fn rays(boxes: usize) -> Vec<Ray> {
+    let middle = (boxes as f32).sqrt();
+    let origin = Point3::new(middle, 20.0, middle);
+    (0..64)
+        .map(|i| {
+            let angle = i as f32 * std::f32::consts::TAU / 64.0;
+            let spread = (i % 8) as f32 * 0.1;
+            let direction = Vector3::new(angle.cos() * spread, -1.0, angle.sin() * spread);
+            Ray::new(origin, direction.normalize())
+        })
+        .collect()
+}
+
+fn brute_force(col_map: &ColliderMap, ray: &Ray) -> Vec<(String, f32)> {
+    let mut hits: Vec<_> = col_map
+        .iter()
+        .flat_map(|(name, trimeshes)| {
+            trimeshes
+                .iter()
+                .filter_map(|trimesh| trimesh.cast_local_ray(ray, 100000.0, true))
+                .map(|toi| (name.clone(), toi))
+        })
+        .collect();
+    hits.sort_by(|a, b| a.1.total_cmp(&b.1));
+    hits
+}
+
+fn picking(c: &mut Criterion) {
+    let mut group = c.benchmark_group("picking");
+    for boxes in STAGE_SIZES {
+        let rays = rays(boxes);
+        let colliders = Colliders::new(synthetic_stage(boxes), HashMap::new());
+        group.bench_with_input(BenchmarkId::new("qbvh", boxes), &rays, |b, rays| {
+            b.iter(|| {
+                for ray in rays {
+                    black_box(colliders.cast_ray(ray, &PickFilter::default()));
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("brute_force", boxes), &rays, |b, rays| {
+            b.iter(|| {
+                for ray in rays {
+                    black_box(brute_force(&colliders.col_map, ray));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn building(c: &mut Criterion) {
+    let mut group = c.benchmark_group("building the picking tree");
+    for boxes in STAGE_SIZES {
+        let col_map = synthetic_stage(boxes);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(boxes),
+            &col_map,
+            |b, col_map| {
+                b.iter_batched(
+                    || col_map.clone(),
+                    |col_map| Colliders::new(col_map, HashMap::new()),
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, picking, building);
+criterion_main!(benches);
@@ -5,13 +5,15 @@ pub mod the_great_mind_palace_of_theatrical_arts;
 
 pub use brainworms_arson::{self, anyhow, egui, egui_winit, nanorand};
 pub use brainworms_farting_noises;
+use brainworms_farting_noises::{AudioCommand, SoundGroup};
 
 pub use cfg_if::cfg_if;
 use egui::{Color32, TextStyle, Visuals};
+pub use gilrs;
 pub use glam;
-use glam::{Mat3A, Vec3};
+use glam::Vec3;
 pub use log;
-use log::info;
+use log::{info, warn};
 pub use parking_lot;
 use parking_lot::Mutex;
 pub use rend3;
@@ -24,27 +26,48 @@ pub use parry3d;
 use rend3::types::DirectionalLight;
 pub use rend3_routine;
 use rend3_routine::base::BaseRenderGraph;
-use std::{
-    sync::Arc,
-    time::{self, Duration},
-};
+pub use serde;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{collections::VecDeque, sync::Arc, time};
 pub use the_great_mind_palace_of_theatrical_arts as theater;
 use theater::{
     basement::{
         cla::GameProgrammeSettings,
         frame_rate::FrameRate,
         grab::Grabber,
-        input_handling::{InputContext, InputStatus},
+        input_handling::{
+            gamepad::{connect_gamepads, poll_gamepad, PadSource},
+            keybindings::capture_rebinding,
+            recording::{InputEvent, InputFeel, InputRecorder, InputRecording, InputReplayer},
+            text_input::{TextEdit, TextInput},
+            InputContext, InputContextStack, InputStatus, KeyBindings, Rebinding, WheelDirection,
+        },
+        settings_file::show_settings_panel,
     },
     play::{
-        backstage::plumbing::{create_base_rendergraph, DefaultRoutines, StoredSurfaceInfo},
+        backstage::{
+            plumbing::{
+                create_base_rendergraph,
+                hot_reload::{watch_for_changes, HotReload},
+                surface_configuration, DefaultRoutines, StoredSurfaceInfo,
+            },
+            prompt_book::SaveMigration,
+            pyrotechnics::transitions::{load_transition_routine, TransitionEffect},
+            stagehands::{SceneChange, StageDirection, TransitionPhase},
+        },
         orchestra::Orchestra,
         scene::{
             actors::AstinkSprite,
-            stage3d::{load_skybox, lock, update_camera_mouse_params},
-            AstinkScene, Camera,
+            camera_moves::CameraMove,
+            camera_paths::CameraRail,
+            hovering::{show_hover_prompt, Hover},
+            interactions::show_dialogue,
+            props::AstinkProp,
+            stage3d::camera_controllers::CameraController,
+            stage3d::{load_skybox, lock},
+            AstinkScene, LoadedStage3D, SceneImplementation,
         },
-        Definitions, Play, Playable,
+        Implementations, Play, Playable,
     },
 };
 pub use tokio;
@@ -54,15 +77,18 @@ pub use wgpu;
 use wgpu::TextureFormat;
 pub use winit;
 use winit::{
-    event::{DeviceEvent, KeyEvent, WindowEvent},
-    event_loop::{ControlFlow, EventLoopWindowTarget},
+    event::{DeviceEvent, ElementState, KeyEvent, WindowEvent},
+    event_loop::{ControlFlow, EventLoopProxy, EventLoopWindowTarget},
     keyboard::PhysicalKey,
     window::{Window, WindowBuilder},
 };
 
 use crate::theater::{
     basement::{frame_rate::update_frame_stats, input_handling::AcceptedInput},
-    play::scene::{actors::draw_actor, stage3d::do_update_camera},
+    play::{
+        curtain::draw_curtain,
+        scene::{actors::draw_actor, stage3d::do_update_camera},
+    },
 };
 
 pub struct GameProgrammeData<
@@ -81,6 +107,11 @@ pub struct GameProgrammeState<InputContextEnum: InputContext> {
     pub last_update: Option<time::Instant>,
     pub frame_rate: FrameRate,
     pub current_playable: Option<Uuid>,
+    /// playables covered by the current one, with the camera they were left at
+    pub playable_stack: Vec<(Uuid, Option<theater::play::scene::Camera>)>,
+    pub stage_directions: VecDeque<(StageDirection, TransitionEffect)>,
+    pub scene_change: Option<SceneChange>,
+    pub event_loop_proxy: Option<EventLoopProxy<MyEvent>>,
     pub grabber: Option<Grabber>,
     pub cur_camera: Option<theater::play::scene::Camera>,
     pub input_status: InputStatus,
@@ -88,8 +119,41 @@ pub struct GameProgrammeState<InputContextEnum: InputContext> {
     pub renderer: Option<Arc<rend3::Renderer>>,
     pub routines: Option<Arc<DefaultRoutines>>,
     pub base_rendergraph: Option<Arc<Mutex<BaseRenderGraph>>>,
+    /// the context being handed input right now, set from `input_contexts` before every
+    /// `handle_input_for_playable`. Change contexts with `push_input_context` and friends
     pub cur_input_context: InputContextEnum,
+    pub input_contexts: InputContextStack<InputContextEnum>,
     pub orchestra: Option<Arc<Orchestra>>,
+    pub surface_format: Option<TextureFormat>,
+    /// only there in dev mode, dropping it stops the hot reloading
+    pub hot_reloader: Option<notify::RecommendedWatcher>,
+    pub settings_panel_open: bool,
+    /// playables hand in their defaults, the player's own come from the settings file
+    pub keybindings: KeyBindings<InputContextEnum>,
+    /// the next key or mouse button pressed goes to this instead of the playable
+    pub rebinding: Option<Rebinding<InputContextEnum>>,
+    /// the real pads, a simulated one, or none at all
+    pub gamepad: Option<Box<dyn PadSource>>,
+    /// how long the frame being handled is, what playables should move things by
+    pub frame_delta: time::Duration,
+    /// there when the input is being taped with `--record-input`
+    pub recorder: Option<InputRecorder>,
+    /// there while a recording plays back, live input is ignored until it's over
+    pub replayer: Option<InputReplayer>,
+    /// there while a terminal is being typed into, it gets the keyboard instead of the playable
+    pub text_input: Option<TextInput>,
+    /// the camera gliding over to another of the scene's cameras
+    pub camera_move: Option<CameraMove>,
+    /// the camera following one of the scene's paths
+    pub camera_rail: Option<CameraRail>,
+    /// how the player gets the camera about in the current scene
+    pub camera_controller: CameraController,
+    /// what the mouse is over in the current scene
+    pub hover: Option<Hover>,
+    /// set by `pick`, answered once the frame's input has been handled
+    pub pick_requested: bool,
+    /// lines brought up by picking something, until they're dismissed
+    pub dialogue: Option<Vec<String>>,
 }
 pub struct GameProgramme<
     PlayablesEnum: Playable<InputContextEnum, UserData>,
@@ -101,21 +165,27 @@ pub struct GameProgramme<
     pub settings: GameProgrammeSettings,
     pub user_data: Arc<Mutex<UserData>>,
     pub rts: Option<tokio::runtime::Runtime>,
+    /// for save files written by older versions of the game
+    pub save_migration: Option<SaveMigration>,
 }
-pub type MyEvent = MyWinitEvent<AstinkScene, AstinkSprite>;
+pub type MyEvent = MyWinitEvent<LoadedStage3D, AstinkSprite, AstinkProp>;
 pub type Event = winit::event::Event<MyEvent>;
 
 #[derive(Debug, Clone, PartialEq)]
-pub enum MyWinitEvent<TS, TA: 'static> {
+pub enum MyWinitEvent<TS, TA: 'static, TP: 'static> {
     /// Custom user event types
     Stage3D(TS),
+    /// the playable whose stage won't load
+    StageMissing(Uuid),
     Actress(TA),
+    Prop(TP),
+    HotReload(HotReload),
 }
 
 impl<
         PlayablesEnum: Playable<InputContextEnum, UserData> + 'static,
         InputContextEnum: InputContext + 'static,
-        UserData: Default + 'static,
+        UserData: Default + Serialize + DeserializeOwned + 'static,
     > GameProgramme<PlayablesEnum, InputContextEnum, UserData>
 {
     pub async fn async_start(mut self, window_builder: WindowBuilder) {
@@ -162,7 +232,14 @@ impl<
                 &iad.device,
                 format,
                 glam::UVec2::new(window_size.width, window_size.height),
-                rend3::types::PresentMode::Immediate,
+                self.present_mode(),
+            );
+            s.configure(
+                &iad.device,
+                &surface_configuration(
+                    glam::UVec2::new(window_size.width, window_size.height),
+                    self.present_mode(),
+                ),
             );
             format
         });
@@ -193,6 +270,7 @@ impl<
                     &base_rendergraph.interfaces,
                     format,
                 )),
+                transition: Mutex::new(load_transition_routine(&renderer, format)),
             }));
         }
         self.state.base_rendergraph = Some(base_rendergraph);
@@ -262,6 +340,27 @@ impl<
         .unwrap();
     }
 
+    /// Everything a frame does between taking input and drawing: hand the input to the current
+    /// playable, answer whatever it asked for and take the camera along. `AboutToWait` and
+    /// [`replay_headless`](Self::replay_headless) both go through here, with or without a window.
+    pub fn play_frame(&mut self, window: Option<&Arc<Window>>) {
+        self.state.start_input_frame();
+        let current_scene = self
+            .state
+            .current_playable
+            .and_then(|id| self.data.play.playables.get_mut(&id));
+        if let Some(current_scene) = current_scene {
+            self.state.hand_input_to_contexts(|state| {
+                current_scene.handle_input_for_playable(&self.settings, state, window)
+            });
+        }
+        self.rig_camera_move();
+        self.rig_camera_path();
+        self.rig_hover();
+        self.rig_pick();
+        self.advance_camera();
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn handle_event(
         &mut self,
@@ -298,19 +397,48 @@ impl<
                 // Insert egui commands here
                 let current_scene_id = game_state.current_playable.unwrap();
                 let current_scene = game_data.play.playables.get_mut(&current_scene_id).unwrap();
+                // keep the loading curtain up until the stage is actually there
+                let still_loading = matches!(
+                    current_scene.playable_implementation(),
+                    Some(Implementations::SceneImplementation(SceneImplementation {
+                        stage3d: AstinkScene::Loading,
+                        ..
+                    }))
+                );
+                let chorus_id = match game_data.play.loading_curtain {
+                    Some(curtain_id) if still_loading => curtain_id,
+                    _ => current_scene_id,
+                };
+                let chorus = game_data.play.playables.get_mut(&chorus_id).unwrap();
+                if let Some(Implementations::CurtainImplementation(curtain)) =
+                    chorus.playable_implementation()
+                {
+                    draw_curtain(
+                        curtain,
+                        &renderer,
+                        game_state.egui_routine.as_mut().unwrap(),
+                        game_data.timestamp_start.elapsed().as_secs_f32(),
+                        game_state.last_update.unwrap().elapsed().as_secs_f32(),
+                    );
+                }
 
-                current_scene.implement_chorus_for_playable(
+                let orchestra = game_state.orchestra.as_ref().unwrap().clone();
+                chorus.implement_chorus_for_playable(
                     egui_ctx.clone(),
-                    game_state.orchestra.as_ref().unwrap().clone(),
+                    orchestra,
                     &self.settings,
+                    game_state,
                     self.user_data.clone(),
                 );
+                show_hover_prompt(&egui_ctx, game_state, self.settings.handedness);
+                show_dialogue(&egui_ctx, game_state);
                 egui::Window::new("FPS").show(&egui_ctx, |ui| {
                     ui.label(std::format!(
                         "framerate: {:.0}fps",
                         game_state.frame_rate.get()
                     ))
                 });
+                show_settings_panel(&egui_ctx, &mut self.settings, game_state);
                 // End the UI frame. Now let's draw the UI with our Backend, we could also
                 // handle the output here
                 let egui::FullOutput {
@@ -378,15 +506,47 @@ impl<
 
                 graph.execute(&renderer, &mut eval_output);
 
-                if let Some(theater::play::Implementations::SceneImplementation(
-                    ref mut cs_implementation,
-                )) = game_data
-                    .play
-                    .playables
-                    .get_mut(&current_scene_id)
-                    .unwrap()
-                    .playable_implementation()
-                    .as_mut()
+                if let Some(scene_change) = game_state.scene_change.as_mut() {
+                    let mut transition_routine = lock(&routines.transition);
+                    let mut finished = transition_routine.is_none();
+                    if let Some(transition_routine) = transition_routine.as_mut() {
+                        match scene_change.phase {
+                            TransitionPhase::Capture => {
+                                transition_routine.capture(&renderer, &frame.texture);
+                                scene_change.phase = TransitionPhase::Switch;
+                            }
+                            // the stagehands haven't swapped the playables yet
+                            TransitionPhase::Switch => {}
+                            TransitionPhase::Blend(started) => {
+                                let (progress, done) =
+                                    scene_change.effect.progress(started.elapsed());
+                                transition_routine.draw(
+                                    &renderer,
+                                    &frame.texture,
+                                    scene_change.effect.kind,
+                                    progress,
+                                    game_data.timestamp_start.elapsed().as_secs_f32(),
+                                );
+                                if done {
+                                    transition_routine.finish();
+                                    finished = true;
+                                }
+                            }
+                        }
+                    }
+                    if finished {
+                        game_state.scene_change = None;
+                    }
+                }
+
+                if let Some(Implementations::SceneImplementation(ref mut cs_implementation)) =
+                    game_data
+                        .play
+                        .playables
+                        .get_mut(&current_scene_id)
+                        .unwrap()
+                        .playable_implementation()
+                        .as_mut()
                 {
                     let t = game_data.timestamp_start.elapsed().as_secs_f32();
                     let dt = game_state.last_update.unwrap().elapsed().as_secs_f32();
@@ -408,14 +568,70 @@ impl<
                 control_flow(winit::event_loop::ControlFlow::Poll);
             }
             Event::AboutToWait => {
-                let current_scene_id = game_state.current_playable.as_ref().unwrap();
-                let current_scene = game_data.play.playables.get_mut(current_scene_id).unwrap();
-
-                current_scene.handle_input_for_playable(&self.settings, game_state, &window);
+                poll_gamepad(game_state, self.settings.deadzone);
+                self.play_frame(Some(&window));
+                self.follow_stage_directions(event_loop_window_target);
+                self.state.input_status.end_frame();
 
                 window.request_redraw();
             }
             Event::WindowEvent { event, .. } => {
+                // a rebinding in the settings panel gets the next press before egui does
+                let pressed = match &event {
+                    WindowEvent::KeyboardInput {
+                        event:
+                            KeyEvent {
+                                physical_key: PhysicalKey::Code(key_code),
+                                state,
+                                ..
+                            },
+                        ..
+                    } => vec![(AcceptedInput::KB(*key_code), *state)],
+                    WindowEvent::MouseInput { button, state, .. } => {
+                        vec![(AcceptedInput::M(*button), *state)]
+                    }
+                    WindowEvent::MouseWheel { delta, .. } => WheelDirection::of(*delta)
+                        .into_iter()
+                        .map(|direction| (AcceptedInput::Wheel(direction), ElementState::Released))
+                        .collect(),
+                    // egui wants these too, so they're kept track of before it gets them
+                    WindowEvent::ModifiersChanged(modifiers) => {
+                        game_state
+                            .take_input(&self.settings, InputEvent::Modifiers(modifiers.state()));
+                        vec![]
+                    }
+                    _ => vec![],
+                };
+                for (input, state) in pressed {
+                    if capture_rebinding(
+                        &mut game_state.keybindings,
+                        &mut game_state.rebinding,
+                        input,
+                        state,
+                        game_state.input_status.modifiers,
+                    ) {
+                        return;
+                    }
+                }
+                // a terminal being typed into gets key presses before egui or the keybindings,
+                // releases still go through for whatever was held down before typing started
+                if game_state.is_typing() {
+                    let typed = match &event {
+                        WindowEvent::KeyboardInput { event, .. } if event.state.is_pressed() => {
+                            Some(TextEdit::of_key(event, game_state.input_status.modifiers))
+                        }
+                        WindowEvent::Ime(ime) => Some(TextEdit::of_ime(ime)),
+                        _ => None,
+                    };
+                    // shortcuts type nothing, but they don't go anywhere else either
+                    if let Some(edit) = typed {
+                        if let Some(edit) = edit {
+                            game_state.take_input(&self.settings, InputEvent::Text(edit));
+                        }
+                        return;
+                    }
+                }
+
                 // Pass the window events to the egui integration.
 
                 let egui_platform = game_state.egui_platform.as_mut().unwrap();
@@ -425,13 +641,15 @@ impl<
 
                 match event {
                     WindowEvent::CloseRequested => {
-                        let the_rt = self.rts.take();
-                        the_rt.unwrap().shutdown_timeout(Duration::from_secs(10));
-                        event_loop_window_target.exit();
+                        self.bring_down_the_curtain(event_loop_window_target);
                     }
                     winit::event::WindowEvent::Resized(size) => {
                         let egui_routine = game_state.egui_routine.as_mut().unwrap();
                         egui_routine.resize(size.width, size.height, window.scale_factor() as f32);
+                        game_state.take_input(
+                            &self.settings,
+                            InputEvent::Resized(size.width, size.height),
+                        );
                     }
 
                     WindowEvent::KeyboardInput {
@@ -444,22 +662,30 @@ impl<
                         ..
                     } => {
                         log::debug!("pressed {:?}", key_code);
-                        game_state
-                            .input_status
-                            .buttons
-                            .insert(AcceptedInput::KB(key_code), state);
+                        game_state.take_input(
+                            &self.settings,
+                            InputEvent::Button(AcceptedInput::KB(key_code), state),
+                        );
                     }
                     WindowEvent::MouseInput { button, state, .. } => {
-                        game_state
-                            .input_status
-                            .buttons
-                            .insert(AcceptedInput::M(button), state);
+                        game_state.take_input(
+                            &self.settings,
+                            InputEvent::Button(AcceptedInput::M(button), state),
+                        );
+                    }
+                    WindowEvent::MouseWheel { delta, .. } => {
+                        for direction in WheelDirection::of(delta) {
+                            game_state.take_input(&self.settings, InputEvent::Wheel(direction));
+                        }
                     }
                     WindowEvent::CursorMoved {
                         device_id: _,
                         position,
                     } => {
-                        game_state.input_status.mouse_physical_poz = position;
+                        game_state.take_input(
+                            &self.settings,
+                            InputEvent::CursorMoved(position.x, position.y),
+                        );
                     }
 
                     _ => {}
@@ -473,29 +699,31 @@ impl<
                     },
                 ..
             } => {
-                update_camera_mouse_params(
-                    self.settings.absolute_mouse,
-                    game_state,
-                    delta_x,
-                    delta_y,
-                );
+                game_state.take_input(&self.settings, InputEvent::MouseMotion(delta_x, delta_y));
             }
-            Event::UserEvent(MyWinitEvent::Stage3D(AstinkScene::Loaded((name, sc_id, scdata)))) => {
+            Event::UserEvent(MyWinitEvent::Stage3D(stage)) => {
                 info!(
                     "Actually caught the user event and assigned the stage3d data to current scene"
                 );
-                if let theater::play::Implementations::SceneImplementation(sc_imp) = game_data
+                self.put_up_stage(stage);
+            }
+            Event::UserEvent(MyWinitEvent::StageMissing(playable_id)) => {
+                self.go_on_without_stage(playable_id);
+            }
+            Event::UserEvent(MyWinitEvent::Prop(AstinkProp::Loaded((name, sc_id, prop)))) => {
+                match game_data
                     .play
                     .playables
                     .get_mut(&sc_id)
-                    .unwrap()
-                    .playable_implementation()
-                    .as_mut()
-                    .unwrap()
+                    .and_then(|p| p.playable_implementation().as_mut())
                 {
-                    sc_imp.stage3d = AstinkScene::Loaded((name, sc_id, scdata));
+                    Some(Implementations::SceneImplementation(sc_imp)) => {
+                        sc_imp.props.insert(name, prop);
+                    }
+                    _ => info!("{name} finished loading after its playable was struck"),
                 }
             }
+            Event::UserEvent(MyWinitEvent::HotReload(change)) => self.hot_reload(change),
             Event::UserEvent(MyWinitEvent::Actress(AstinkSprite::Loaded((
                 name,
                 sc_id,
@@ -503,19 +731,19 @@ impl<
             )))) => {
                 info!("Actually caught the user event and assigned sprite data to {name}");
 
-                if let theater::play::Implementations::SceneImplementation(sc_imp) = game_data
+                match game_data
                     .play
                     .playables
                     .get_mut(&sc_id)
-                    .unwrap()
-                    .playable_implementation()
-                    .as_mut()
-                    .unwrap()
+                    .and_then(|p| p.playable_implementation().as_mut())
                 {
-                    sc_imp.actresses.insert(
-                        name.clone(),
-                        Arc::new(Mutex::new(AstinkSprite::Loaded((name, sc_id, acdata)))),
-                    );
+                    Some(Implementations::SceneImplementation(sc_imp)) => {
+                        sc_imp.actresses.insert(
+                            name.clone(),
+                            Arc::new(Mutex::new(AstinkSprite::Loaded((name, sc_id, acdata)))),
+                        );
+                    }
+                    _ => info!("{name} finished loading after its playable was struck"),
                 }
             }
             _ => {}
@@ -593,43 +821,55 @@ impl<
         state.frame_rate = FrameRate::new(100);
         state.current_playable = None;
         state.cur_camera = None;
-        state.input_status = InputStatus::default();
-        state.orchestra = Some(Arc::new(Orchestra::new(
-            self.rts.as_ref().unwrap().handle().clone(),
-        )));
+        state.input_status = InputStatus {
+            window_size,
+            ..Default::default()
+        };
+        if let Some(path) = &self.settings.replay_input {
+            match InputRecording::load(path) {
+                Ok(recording) => {
+                    info!("replaying input from {}", path.display());
+                    state.replayer = Some(InputReplayer::new(recording));
+                }
+                Err(e) => warn!("not replaying {}: {e}", path.display()),
+            }
+        } else if self.settings.record_input.is_some() {
+            state.recorder = Some(InputRecorder::new(InputFeel::of(&self.settings)));
+            state.record_input(InputEvent::Resized(window_size.width, window_size.height));
+        }
+        state
+            .keybindings
+            .load_from(&self.settings.settings_file.keybindings);
+        state.gamepad = connect_gamepads();
+        let orchestra = Arc::new(Orchestra::new(self.rts.as_ref().unwrap().handle().clone()));
+        orchestra.send_cmd(AudioCommand::SetVolume(
+            SoundGroup::BGM,
+            self.settings.bgm_volume,
+        ));
+        orchestra.send_cmd(AudioCommand::SetVolume(
+            SoundGroup::SFX,
+            self.settings.sfx_volume,
+        ));
+        state.orchestra = Some(orchestra);
         //        state.cur_input_context = ;
 
+        state.event_loop_proxy = Some(event_loop.create_proxy());
+        state.surface_format = Some(surface_format);
+        if self.settings.hot_reload {
+            match watch_for_changes(event_loop.create_proxy()) {
+                Ok(watcher) => state.hot_reloader = Some(watcher),
+                Err(e) => warn!("no hot reloading: {e}"),
+            }
+        }
+
         // Implementations for Play/Scene/etc go below
-        let data = &mut self.data;
-        let play = &mut data.play;
-        let state = &mut self.state;
-        state.current_playable = Some(play.first_playable);
-        let scene1 = play.playables.get_mut(&play.first_playable).unwrap();
-
-        // Set camera location data
-        if let Definitions::SceneDefinition(definition) = scene1.playable_definition() {
-            let scene1_starting_cam_info = definition.cameras.get(&definition.start_cam).unwrap();
-            let cur_cam = Camera {
-                name: definition.start_cam.clone(),
-                renderer_camera: rend3::types::Camera::default(),
-                info: scene1_starting_cam_info.clone(),
-                rotation: Mat3A::default(),
-            };
-            state.cur_camera = Some(cur_cam);
+        if let Some(loading_curtain) = self.data.play.loading_curtain {
+            self.rig_playable(loading_curtain);
         }
-        let routines = state.routines.clone().unwrap();
-        let playable_renderer_copy = Arc::clone(&renderer);
-        let playable_routines_copy = Arc::clone(&routines);
-        scene1.implement_playable(
-            &self.settings,
-            event_loop,
-            playable_renderer_copy,
-            playable_routines_copy,
-            self.rts.as_ref().unwrap(),
-            self.state.orchestra.as_ref().unwrap().clone(),
-            self.user_data.clone(),
-        );
+        let first_playable = self.data.play.first_playable;
+        self.stage_playable(first_playable);
 
+        let routines = self.state.routines.clone().unwrap();
         let skybox_renderer_copy = Arc::clone(&renderer);
         let skybox_routines_copy = Arc::clone(&routines);
         self.spawn(async move {
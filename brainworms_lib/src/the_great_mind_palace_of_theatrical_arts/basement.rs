@@ -1,9 +1,11 @@
 pub mod cla;
 #[cfg(feature = "extra_debugging")]
 pub mod debug_profiling_etc;
+pub mod easing;
 pub mod frame_rate;
 pub mod grab;
 pub mod input_handling;
 pub mod logging;
 pub mod quad_damage;
+pub mod settings_file;
 pub mod text_files;
@@ -1,3 +1,5 @@
+use std::{ffi::OsString, path::PathBuf};
+
 use glam::Vec3;
 use pico_args::{self, Arguments};
 use rend3::{
@@ -7,6 +9,8 @@ use rend3::{
 use rend3_routine::pbr::NormalTextureYDirection;
 use wgpu::Backend;
 
+use super::settings_file::{from_settings_file, SettingsFile, SETTINGS_FILE};
+
 const HELP: &str = "\
 scene-viewer
 
@@ -17,6 +21,8 @@ usage: scene-viewer --options
 Meta:
   --help            This menu.
 
+Anything given here wins over settings.toml for this run, without changing the file.
+
 Rendering:
   -b --backend                 Choose backend to run on ('vk', 'dx12', 'dx11', 'metal', 'gl').
   -d --device                  Choose device to run on (case insensitive device substring).
@@ -37,11 +43,16 @@ Assets:
   --scale <scale>                        Scale all objects loaded by this factor. Defaults to 1.0.
   --shadow-distance <value>              Distance from the camera there will be directional shadows. Lower values means higher quality shadows. Defaults to 100.
   --shadow-resolution <value>            Resolution of the shadow map. Higher values mean higher quality shadows with high performance cost. Defaults to 2048.
+  --hot-reload                           Reload scene files, markup, the transition shader and stages when they change on disk. Always on in debug builds.
 
 Controls:
   --walk <speed>               Walk speed (speed without holding shift) in units/second (typically meters). Default 10.
   --run  <speed>               Run speed (speed while holding shift) in units/second (typically meters). Default 50.
+  --mouse-sensitivity <value>  How far the camera turns for the same mouse movement. Default 1.
+  --deadzone <value>           How far a gamepad stick has to move before it counts, 0 to 1. Default 0.15.
   --camera x,y,z,pitch,yaw     Spawns the camera at the given position. Press Period to get the current camera position.
+  --record-input <file>        Tape everything the player does, written to the file on quitting.
+  --replay-input <file>        Play a tape made with --record-input back instead of listening to the player.
 ";
 
 pub(crate) fn extract_backend(value: &str) -> Result<Backend, &'static str> {
@@ -62,6 +73,10 @@ pub(crate) fn extract_profile(value: &str) -> Result<rend3::RendererProfile, &'s
     })
 }
 
+pub(crate) fn extract_msaa_count(value: u32) -> Result<SampleCount, &'static str> {
+    extract_msaa(&value.to_string())
+}
+
 pub(crate) fn extract_msaa(value: &str) -> Result<SampleCount, &'static str> {
     Ok(match value {
         "1" => SampleCount::One,
@@ -147,6 +162,15 @@ pub struct GameProgrammeSettings {
     pub present_mode: rend3::types::PresentMode,
     pub samples: SampleCount,
     pub fullscreen: bool,
+    pub hot_reload: bool,
+    pub mouse_sensitivity: f32,
+    pub deadzone: f32,
+    pub bgm_volume: f32,
+    pub sfx_volume: f32,
+    pub record_input: Option<PathBuf>,
+    pub replay_input: Option<PathBuf>,
+    /// what's in [`SETTINGS_FILE`], the settings panel edits this and saves it
+    pub settings_file: SettingsFile,
     //    pub keybindings: KeyBindings,
     pub handedness: Handedness,
     //    pub keybindings: KeyBindings<InputContextEnum>,
@@ -159,7 +183,13 @@ impl Default for GameProgrammeSettings {
 }
 impl GameProgrammeSettings {
     pub fn new() -> Self {
-        let mut args = Arguments::from_vec(std::env::args_os().skip(1).collect());
+        Self::from_args(std::env::args_os().skip(1).collect())
+    }
+    /// The same as [`new`](Self::new) with `args` instead of the command line, for when there
+    /// isn't one to go by, like in tests.
+    pub fn from_args(args: Vec<OsString>) -> Self {
+        let mut args = Arguments::from_vec(args);
+        let settings_file = SettingsFile::load();
 
         // Meta
         let help = args.contains(["-h", "--help"]);
@@ -168,20 +198,34 @@ impl GameProgrammeSettings {
         let desired_backend = option_arg(
             args.opt_value_from_fn(["-b", "--backend"], extract_backend),
             HELP,
-        );
+        )
+        .or_else(|| {
+            from_settings_file("backend", settings_file.backend.as_deref(), extract_backend)
+        });
         let desired_device_name: Option<String> =
             option_arg(args.opt_value_from_str(["-d", "--device"]), HELP)
+                .or_else(|| settings_file.device.clone())
                 .map(|s: String| s.to_lowercase());
         let desired_mode = option_arg(
             args.opt_value_from_fn(["-p", "--profile"], extract_profile),
             HELP,
-        );
+        )
+        .or_else(|| {
+            from_settings_file("profile", settings_file.profile.as_deref(), extract_profile)
+        });
         let samples = option_arg(args.opt_value_from_fn("--msaa", extract_msaa), HELP)
+            .or_else(|| {
+                let msaa = settings_file.msaa?;
+                extract_msaa_count(msaa)
+                    .map_err(|e| log::warn!("{SETTINGS_FILE}: ignoring msaa = {msaa}: {e}"))
+                    .ok()
+            })
             .unwrap_or(SampleCount::Four);
         let present_mode = option_arg(
             args.opt_value_from_fn(["-v", "--vsync"], extract_vsync),
             HELP,
         )
+        .or_else(|| from_settings_file("vsync", settings_file.vsync.as_deref(), extract_vsync))
         .unwrap_or(rend3::types::PresentMode::Immediate);
 
         // Windowing
@@ -215,10 +259,34 @@ impl GameProgrammeSettings {
             option_arg(args.opt_value_from_str("--shadow-resolution"), HELP).unwrap_or(8192);
         let gltf_disable_directional_light: bool =
             args.contains("--gltf-disable-directional-lights");
+        let hot_reload = args.contains("--hot-reload") || cfg!(debug_assertions);
 
         // Controls
-        let walk_speed = args.value_from_str("--walk").unwrap_or(10.0_f32);
-        let run_speed = args.value_from_str("--run").unwrap_or(50.0_f32);
+        let walk_speed = args
+            .value_from_str("--walk")
+            .ok()
+            .or(settings_file.walk_speed)
+            .unwrap_or(10.0_f32);
+        let run_speed = args
+            .value_from_str("--run")
+            .ok()
+            .or(settings_file.run_speed)
+            .unwrap_or(50.0_f32);
+        let mouse_sensitivity = option_arg(args.opt_value_from_str("--mouse-sensitivity"), HELP)
+            .or(settings_file.mouse_sensitivity)
+            .unwrap_or(1.0_f32);
+        let deadzone = option_arg(args.opt_value_from_str("--deadzone"), HELP)
+            .or(settings_file.deadzone)
+            .unwrap_or(0.15_f32)
+            .clamp(0.0, 0.95);
+        let record_input: Option<PathBuf> =
+            option_arg(args.opt_value_from_str("--record-input"), HELP);
+        let replay_input: Option<PathBuf> =
+            option_arg(args.opt_value_from_str("--replay-input"), HELP);
+
+        // Audio, the orchestra starts out at 0.1 for both
+        let bgm_volume = settings_file.bgm_volume.unwrap_or(0.1);
+        let sfx_volume = settings_file.sfx_volume.unwrap_or(0.1);
 
         let remaining = args.finish();
 
@@ -268,6 +336,14 @@ impl GameProgrammeSettings {
             present_mode,
             samples,
             fullscreen,
+            hot_reload,
+            mouse_sensitivity,
+            deadzone,
+            bgm_volume,
+            sfx_volume,
+            record_input,
+            replay_input,
+            settings_file,
             handedness: Handedness::Right,
             //            def_pause_ctx_kb,
         }
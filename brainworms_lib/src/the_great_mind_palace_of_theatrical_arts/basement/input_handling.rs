@@ -1,20 +1,38 @@
-use std::{collections::HashMap, sync::Arc};
+pub mod combos;
+pub mod context_stack;
+pub mod gamepad;
+pub mod keybindings;
+pub mod recording;
+pub mod text_input;
 
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use gilrs::{Axis, Button};
 use glam::DVec2;
 use log::info;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use winit::{
-    dpi::PhysicalPosition,
+    dpi::{PhysicalPosition, PhysicalSize},
     event::{ElementState, MouseButton},
-    keyboard::KeyCode,
+    keyboard::{KeyCode, ModifiersState},
     window::Window,
 };
 
 use crate::{GameProgrammeSettings, GameProgrammeState};
 
+pub use combos::{Press, WheelDirection};
+pub use context_stack::{InputContextStack, Passing};
+pub use gamepad::AxisDirection;
+pub use keybindings::{KeyBindings, Rebinding};
+
 pub type KeyStates = HashMap<AcceptedInput, ElementState>;
-pub type KeyBindings<TO> = HashMap<TO, AcceptedInput>;
 
-#[derive(Debug, Hash, Eq, PartialEq, Copy, Clone)]
+#[derive(Debug, Hash, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
 pub enum DebugInputContext {
     Sprint,
     Forwards,
@@ -29,15 +47,65 @@ pub enum DebugInputContext {
     SwitchToScene,
     Marker,
 }
+/// Serialisable so the current one can go in a save file.
 pub trait InputContext:
-    std::default::Default + std::fmt::Debug + std::hash::Hash + Eq + PartialEq
+    std::default::Default
+    + std::fmt::Debug
+    + Clone
+    + std::hash::Hash
+    + Eq
+    + PartialEq
+    + Serialize
+    + DeserializeOwned
 {
+    /// Which input context the action is in. Actions in the same one can't share an input, since
+    /// it's listened to all at once. The whole enum is one context unless this says otherwise,
+    /// nested enums go by their variant.
+    fn context(&self) -> impl Eq + std::hash::Hash {}
 }
 
-#[derive(Debug, Eq, PartialEq, Hash)]
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone, Serialize, Deserialize)]
 pub enum AcceptedInput {
     KB(KeyCode),
     M(MouseButton),
+    /// gamepad button
+    GB(Button),
+    /// gamepad axis pushed far enough one way
+    GA(Axis, AxisDirection),
+    /// a notch of the mouse wheel
+    Wheel(WheelDirection),
+    /// pressed while holding the modifiers, like ctrl+s or shift+click
+    Chord(ModifiersState, Press),
+    DoubleClick(Press),
+    LongPress(Press),
+}
+impl AcceptedInput {
+    /// The press a chord, double click or long press is made of.
+    pub fn press(&self) -> Option<Press> {
+        match self {
+            AcceptedInput::Chord(_, press)
+            | AcceptedInput::DoubleClick(press)
+            | AcceptedInput::LongPress(press) => Some(*press),
+            _ => None,
+        }
+    }
+}
+impl fmt::Display for AcceptedInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AcceptedInput::KB(key_code) => write!(f, "{key_code:?}"),
+            AcceptedInput::M(button) => write!(f, "mouse {button:?}"),
+            AcceptedInput::GB(button) => write!(f, "pad {button:?}"),
+            AcceptedInput::GA(axis, AxisDirection::Positive) => write!(f, "pad {axis:?}+"),
+            AcceptedInput::GA(axis, AxisDirection::Negative) => write!(f, "pad {axis:?}-"),
+            AcceptedInput::Wheel(direction) => write!(f, "wheel {direction:?}"),
+            AcceptedInput::Chord(modifiers, press) => {
+                write!(f, "{}{press}", combos::modifier_prefix(*modifiers))
+            }
+            AcceptedInput::DoubleClick(press) => write!(f, "double {press}"),
+            AcceptedInput::LongPress(press) => write!(f, "hold {press}"),
+        }
+    }
 }
 
 // Ideally I want this to only mess with its own data but for now let's just reproduce the existing behaviour
@@ -47,7 +115,8 @@ pub trait HandlesInputContexts<InputContextEnum: InputContext> {
         &mut self,
         settings: &GameProgrammeSettings,
         state: &mut GameProgrammeState<InputContextEnum>,
-        window: &Arc<Window>,
+        /// `None` when there's no window at all, like when replaying a recording headless
+        window: Option<&Arc<Window>>,
     );
     fn key_down(input_status: &KeyStates, the_input: &AcceptedInput) -> Option<bool> {
         Self::key_is_state(input_status, the_input, &ElementState::Pressed)
@@ -68,37 +137,174 @@ pub trait HandlesInputContexts<InputContextEnum: InputContext> {
             .get(the_input)
             .map(|k| k.is_pressed() && want_pressed || !k.is_pressed() && !want_pressed)
     }
+    /// Whether any of the inputs bound to `binding` is down, `None` when none of them has been
+    /// touched.
     fn input_down(
         input_status: &KeyStates,
         keybindings: &KeyBindings<InputContextEnum>,
         binding: &InputContextEnum,
     ) -> Option<bool> {
-        match keybindings.get(binding) {
-            Some(the_key) => Self::key_down(input_status, the_key),
-            None => {
-                info!("No binding for {:?}", binding);
-                None
-            }
-        }
+        Self::any_input_is_state(input_status, keybindings, binding, &ElementState::Pressed)
     }
     fn input_up(
         input_status: &KeyStates,
         keybindings: &KeyBindings<InputContextEnum>,
         binding: &InputContextEnum,
     ) -> Option<bool> {
-        match keybindings.get(binding) {
-            Some(the_key) => Self::key_up(input_status, the_key),
-            None => {
-                info!("No binding for {:?}", binding);
-                None
-            }
+        Self::any_input_is_state(input_status, keybindings, binding, &ElementState::Released)
+    }
+    fn any_input_is_state(
+        input_status: &KeyStates,
+        keybindings: &KeyBindings<InputContextEnum>,
+        binding: &InputContextEnum,
+        state: &ElementState,
+    ) -> Option<bool> {
+        if !keybindings.is_bound(binding) {
+            info!("No binding for {:?}", binding);
+            return None;
         }
+        keybindings
+            .inputs(binding)
+            .iter()
+            .filter_map(|the_input| Self::key_is_state(input_status, the_input, state))
+            .reduce(|a, b| a || b)
+    }
+    /// Whether any of the inputs bound to `binding` went down since the last frame.
+    fn input_just_pressed(
+        input_status: &InputStatus,
+        keybindings: &KeyBindings<InputContextEnum>,
+        binding: &InputContextEnum,
+    ) -> bool {
+        keybindings
+            .inputs(binding)
+            .iter()
+            .any(|the_input| input_status.just_pressed(the_input))
+    }
+    /// Whether any of the inputs bound to `binding` came up since the last frame.
+    fn input_just_released(
+        input_status: &InputStatus,
+        keybindings: &KeyBindings<InputContextEnum>,
+        binding: &InputContextEnum,
+    ) -> bool {
+        keybindings
+            .inputs(binding)
+            .iter()
+            .any(|the_input| input_status.just_released(the_input))
+    }
+    /// Whether any of the inputs bound to `binding` has been held down for at least `duration`.
+    fn input_held_for(
+        input_status: &InputStatus,
+        keybindings: &KeyBindings<InputContextEnum>,
+        binding: &InputContextEnum,
+        duration: Duration,
+    ) -> bool {
+        keybindings
+            .inputs(binding)
+            .iter()
+            .any(|the_input| input_status.held_for(the_input, duration))
     }
 }
 
 #[derive(Debug, Default)]
 pub struct InputStatus {
     pub buttons: KeyStates,
+    /// gamepad sticks and triggers, deadzone already taken off
+    pub axes: HashMap<Axis, f32>,
+    pub modifiers: ModifiersState,
+    pub timing: combos::PressTiming,
+    /// what went down or came up since the last frame
+    pub just_pressed: HashSet<AcceptedInput>,
+    pub just_released: HashSet<AcceptedInput>,
+    /// when everything that's down went down
+    pub pressed_at: HashMap<AcceptedInput, Instant>,
+    /// held down from before the input context changed, ignored until let go of
+    pub swallowed: HashSet<AcceptedInput>,
     pub last_mouse_delta: Option<DVec2>,
     pub mouse_physical_poz: PhysicalPosition<f64>,
+    /// what the mouse position is relative to, kept here so a replay doesn't need a window
+    pub window_size: PhysicalSize<u32>,
+    /// when the frame being handled started, on the recording's clock while one plays back
+    pub now: Option<Instant>,
+}
+
+impl InputStatus {
+    /// Record `input` going down or up at `now`. Going down again while it's down, like key
+    /// repeat does, isn't a new press.
+    pub fn set_at(&mut self, input: AcceptedInput, state: ElementState, now: Instant) {
+        if self.swallow(input, state) {
+            return;
+        }
+        let was_pressed = self.is_pressed(&input);
+        // went down while something else had the keyboard, like a terminal being typed into
+        if !state.is_pressed() && !was_pressed {
+            return;
+        }
+        self.buttons.insert(input, state);
+        match state {
+            ElementState::Pressed if !was_pressed => {
+                self.just_pressed.insert(input);
+                self.pressed_at.insert(input, now);
+            }
+            ElementState::Pressed => {}
+            ElementState::Released => {
+                self.just_released.insert(input);
+                self.pressed_at.remove(&input);
+            }
+        }
+    }
+    pub fn is_pressed(&self, input: &AcceptedInput) -> bool {
+        self.buttons.get(input).is_some_and(|s| s.is_pressed())
+    }
+    pub fn just_pressed(&self, input: &AcceptedInput) -> bool {
+        self.just_pressed.contains(input)
+    }
+    pub fn just_released(&self, input: &AcceptedInput) -> bool {
+        self.just_released.contains(input)
+    }
+    /// Down since at least `duration` before the frame started.
+    pub fn held_for(&self, input: &AcceptedInput, duration: Duration) -> bool {
+        let Some(now) = self.now else {
+            return false;
+        };
+        self.pressed_at
+            .get(input)
+            .is_some_and(|t| now.saturating_duration_since(*t) >= duration)
+    }
+    /// Act like `input` never happened, for when something else already dealt with it.
+    pub fn forget(&mut self, input: &AcceptedInput) {
+        self.buttons.remove(input);
+        self.just_pressed.remove(input);
+        self.just_released.remove(input);
+        self.pressed_at.remove(input);
+    }
+    /// Stop paying attention to everything held down until it's let go of, and forget this
+    /// frame's presses and releases. Whoever listens next starts from nothing pressed, without a
+    /// release turning up for something it never saw go down.
+    pub fn swallow_held(&mut self) {
+        let held = self
+            .buttons
+            .iter()
+            .filter(|(input, state)| state.is_pressed() && input.press().is_none())
+            .map(|(input, _)| *input);
+        // chords, double clicks and long presses end with the press they're made of
+        self.swallowed.extend(held);
+        self.buttons.clear();
+        self.just_pressed.clear();
+        self.just_released.clear();
+        self.pressed_at.clear();
+        self.timing = Default::default();
+    }
+    /// Whether `input` belongs to a press that was swallowed, letting go of it if it's the release.
+    fn swallow(&mut self, input: AcceptedInput, state: ElementState) -> bool {
+        match state {
+            ElementState::Pressed => self.swallowed.contains(&input),
+            ElementState::Released => self.swallowed.remove(&input),
+        }
+    }
+    /// Forget this frame's presses and releases, the engine does this after every `AboutToWait`.
+    pub fn end_frame(&mut self) {
+        self.just_pressed.clear();
+        self.just_released.clear();
+        self.buttons.retain(|_, state| state.is_pressed());
+    }
 }
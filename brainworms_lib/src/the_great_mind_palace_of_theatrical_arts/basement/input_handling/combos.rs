@@ -0,0 +1,193 @@
+// chords, wheel notches, double clicks and long presses, worked out here so playables only ever
+// look up an AcceptedInput
+use std::{
+    collections::HashMap,
+    fmt,
+    time::{Duration, Instant},
+};
+
+use gilrs::Button;
+use serde::{Deserialize, Serialize};
+use winit::{
+    event::{ElementState, MouseButton, MouseScrollDelta},
+    keyboard::{KeyCode, ModifiersState},
+};
+
+use super::{AcceptedInput, InputStatus};
+
+/// Two presses closer together than this are a double click.
+pub const DOUBLE_CLICK: Duration = Duration::from_millis(300);
+/// Held for this long it's a long press.
+pub const LONG_PRESS: Duration = Duration::from_millis(600);
+
+/// Anything that goes down and comes back up, which is what chords, double clicks and long
+/// presses are made of.
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone, Serialize, Deserialize)]
+pub enum Press {
+    KB(KeyCode),
+    M(MouseButton),
+    GB(Button),
+}
+impl Press {
+    pub fn of(input: AcceptedInput) -> Option<Self> {
+        match input {
+            AcceptedInput::KB(key_code) => Some(Self::KB(key_code)),
+            AcceptedInput::M(button) => Some(Self::M(button)),
+            AcceptedInput::GB(button) => Some(Self::GB(button)),
+            _ => None,
+        }
+    }
+    pub fn is_modifier(&self) -> bool {
+        matches!(
+            self,
+            Self::KB(
+                KeyCode::ShiftLeft
+                    | KeyCode::ShiftRight
+                    | KeyCode::ControlLeft
+                    | KeyCode::ControlRight
+                    | KeyCode::AltLeft
+                    | KeyCode::AltRight
+                    | KeyCode::SuperLeft
+                    | KeyCode::SuperRight
+            )
+        )
+    }
+}
+impl From<Press> for AcceptedInput {
+    fn from(press: Press) -> Self {
+        match press {
+            Press::KB(key_code) => Self::KB(key_code),
+            Press::M(button) => Self::M(button),
+            Press::GB(button) => Self::GB(button),
+        }
+    }
+}
+impl fmt::Display for Press {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        AcceptedInput::from(*self).fmt(f)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone, Serialize, Deserialize)]
+pub enum WheelDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+impl WheelDirection {
+    /// Which ways a scroll went, a diagonal one going two ways at once.
+    pub fn of(delta: MouseScrollDelta) -> Vec<Self> {
+        let (x, y) = match delta {
+            MouseScrollDelta::LineDelta(x, y) => (x as f64, y as f64),
+            MouseScrollDelta::PixelDelta(position) => (position.x, position.y),
+        };
+        let mut directions = vec![];
+        if y > 0.0 {
+            directions.push(Self::Up);
+        } else if y < 0.0 {
+            directions.push(Self::Down);
+        }
+        if x > 0.0 {
+            directions.push(Self::Right);
+        } else if x < 0.0 {
+            directions.push(Self::Left);
+        }
+        directions
+    }
+}
+
+/// `ctrl+shift+` and so on, for showing a chord to the player.
+pub(crate) fn modifier_prefix(modifiers: ModifiersState) -> String {
+    [
+        (ModifiersState::CONTROL, "ctrl+"),
+        (ModifiersState::SHIFT, "shift+"),
+        (ModifiersState::ALT, "alt+"),
+        (ModifiersState::SUPER, "super+"),
+    ]
+    .into_iter()
+    .filter(|(modifier, _)| modifiers.contains(*modifier))
+    .map(|(_, prefix)| prefix)
+    .collect()
+}
+
+#[derive(Debug, Default)]
+pub struct PressTiming {
+    /// the last press that could still turn into a double click
+    last_press: HashMap<Press, Instant>,
+}
+
+impl InputStatus {
+    pub fn feed(&mut self, input: AcceptedInput, state: ElementState) {
+        self.feed_at(input, state, Instant::now());
+    }
+    /// Record `input` going down or up at `now`, along with every chord or double click it makes.
+    pub fn feed_at(&mut self, input: AcceptedInput, state: ElementState, now: Instant) {
+        if self.swallow(input, state) {
+            return;
+        }
+        // key repeat
+        let repeat = state.is_pressed() && self.is_pressed(&input);
+        self.set_at(input, state, now);
+        let Some(press) = Press::of(input) else {
+            return;
+        };
+        match state {
+            ElementState::Pressed if repeat => {}
+            ElementState::Pressed => {
+                if !self.modifiers.is_empty() && !press.is_modifier() {
+                    self.set_at(AcceptedInput::Chord(self.modifiers, press), state, now);
+                }
+                let last_press = self.timing.last_press.insert(press, now);
+                if last_press.is_some_and(|t| now.duration_since(t) <= DOUBLE_CLICK) {
+                    self.set_at(AcceptedInput::DoubleClick(press), state, now);
+                    // a third click starts over instead of being another double
+                    self.timing.last_press.remove(&press);
+                }
+            }
+            ElementState::Released => {
+                // whatever this press started ends with it, whatever the modifiers are by now
+                let started: Vec<_> = self
+                    .buttons
+                    .iter()
+                    .filter(|(other, other_state)| {
+                        other_state.is_pressed() && other.press() == Some(press)
+                    })
+                    .map(|(other, _)| *other)
+                    .collect();
+                for other in started {
+                    self.set_at(other, state, now);
+                }
+            }
+        }
+    }
+    /// A notch of the wheel goes down and up at once, so it shows up as just pressed and just
+    /// released in the same frame.
+    pub fn feed_wheel(&mut self, delta: MouseScrollDelta) {
+        for direction in WheelDirection::of(delta) {
+            self.feed_wheel_direction(direction);
+        }
+    }
+    pub fn feed_wheel_direction(&mut self, direction: WheelDirection) {
+        let input = AcceptedInput::Wheel(direction);
+        self.just_pressed.insert(input);
+        self.buttons.insert(input, ElementState::Released);
+        self.just_released.insert(input);
+    }
+    /// Start the frame at `now`, turning everything held for [`LONG_PRESS`] into a long press,
+    /// once per press.
+    pub fn update_long_presses(&mut self, now: Instant) {
+        self.now = Some(now);
+        let long_presses: Vec<_> = self
+            .pressed_at
+            .iter()
+            .filter(|(_, since)| now.duration_since(**since) >= LONG_PRESS)
+            .filter_map(|(input, _)| Press::of(*input))
+            .map(AcceptedInput::LongPress)
+            .filter(|long_press| !self.is_pressed(long_press))
+            .collect();
+        for long_press in long_presses {
+            self.set_at(long_press, ElementState::Pressed, now);
+        }
+    }
+}
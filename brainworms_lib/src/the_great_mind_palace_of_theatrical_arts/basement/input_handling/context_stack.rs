@@ -0,0 +1,120 @@
+// input contexts piled on top of each other, a pause menu or a dialogue box going over whatever
+// was listening before and handing input back when it's popped
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::GameProgrammeState;
+
+use super::InputContext;
+
+/// Whether a context lets the ones underneath it hear input too.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Passing {
+    /// nothing underneath hears a thing, for menus and dialogue that take over
+    #[default]
+    Consume,
+    /// everything underneath hears everything as well, for overlays
+    PassThrough,
+}
+
+/// Never empty, the bottom context stays put whatever gets pushed and popped over it.
+#[derive(Debug, Clone)]
+pub struct InputContextStack<InputContextEnum> {
+    /// bottom first
+    stack: Vec<(InputContextEnum, Passing)>,
+    /// whether anything got pushed, popped or switched since the last look
+    changed: bool,
+}
+impl<InputContextEnum: InputContext> Default for InputContextStack<InputContextEnum> {
+    fn default() -> Self {
+        Self::new(InputContextEnum::default())
+    }
+}
+
+impl<InputContextEnum: InputContext> InputContextStack<InputContextEnum> {
+    pub fn new(base: InputContextEnum) -> Self {
+        Self {
+            stack: vec![(base, Passing::Consume)],
+            changed: true,
+        }
+    }
+    pub fn top(&self) -> &InputContextEnum {
+        &self.stack.last().unwrap().0
+    }
+    pub fn base(&self) -> &InputContextEnum {
+        &self.stack[0].0
+    }
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+    pub fn push(&mut self, context: InputContextEnum, passing: Passing) {
+        self.stack.push((context, passing));
+        self.changed = true;
+    }
+    /// Take the top context off, unless it's the only one left.
+    pub fn pop(&mut self) -> Option<InputContextEnum> {
+        if self.stack.len() == 1 {
+            warn!("can't pop {:?}, it's the last input context", self.top());
+            return None;
+        }
+        self.changed = true;
+        self.stack.pop().map(|(context, _)| context)
+    }
+    /// Put `context` in place of the top one, passing input on the way that one did.
+    pub fn switch(&mut self, context: InputContextEnum) {
+        self.stack.last_mut().unwrap().0 = context;
+        self.changed = true;
+    }
+    /// Everything hears input this frame, top down: the top context and whatever it passes
+    /// through to, as far as the first one that consumes.
+    pub fn listening(&self) -> Vec<InputContextEnum> {
+        let mut listening = vec![];
+        for (context, passing) in self.stack.iter().rev() {
+            listening.push(context.clone());
+            if *passing == Passing::Consume {
+                break;
+            }
+        }
+        listening
+    }
+    fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+}
+
+impl<InputContextEnum: InputContext> GameProgrammeState<InputContextEnum> {
+    /// Put `context` over the current ones. Whatever's held down stays with the ones underneath.
+    pub fn push_input_context(&mut self, context: InputContextEnum, passing: Passing) {
+        self.input_contexts.push(context, passing);
+        self.input_status.swallow_held();
+    }
+    pub fn pop_input_context(&mut self) -> Option<InputContextEnum> {
+        let popped = self.input_contexts.pop();
+        self.input_status.swallow_held();
+        popped
+    }
+    /// Swap the top context for `context`, like going between debug and scene controls.
+    pub fn switch_input_context(&mut self, context: InputContextEnum) {
+        self.input_contexts.switch(context);
+        self.input_status.swallow_held();
+    }
+    /// Throw the whole stack away and start again from `base`.
+    pub fn reset_input_contexts(&mut self, base: InputContextEnum) {
+        self.input_contexts = InputContextStack::new(base);
+        self.input_status.swallow_held();
+    }
+
+    /// Call `handle` once for each listening context, top down, with
+    /// [`GameProgrammeState::cur_input_context`] set to it. Once a context pushes, pops or
+    /// switches, the ones further down don't get this frame's input.
+    pub fn hand_input_to_contexts(&mut self, mut handle: impl FnMut(&mut Self)) {
+        self.input_contexts.take_changed();
+        for context in self.input_contexts.listening() {
+            self.cur_input_context = context;
+            handle(self);
+            if self.input_contexts.take_changed() {
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,194 @@
+// gamepads: buttons go in with the keys, sticks and triggers get deadzoned and kept as axes
+use std::{collections::VecDeque, time::Instant};
+
+use gilrs::{Axis, Button, EventType, Gilrs};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use winit::event::ElementState;
+
+use crate::GameProgrammeState;
+
+use super::{
+    keybindings::capture_rebinding, recording::InputEvent, AcceptedInput, InputContext, InputStatus,
+};
+
+/// How far an axis has to go before it counts as pressed when it's bound like a button.
+pub const AXIS_PRESS_THRESHOLD: f32 = 0.5;
+
+/// Which way an axis has to be pushed for an [`AcceptedInput::GA`] binding.
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone, Serialize, Deserialize)]
+pub enum AxisDirection {
+    Positive,
+    Negative,
+}
+impl AxisDirection {
+    pub fn of(value: f32) -> Self {
+        if value < 0.0 {
+            Self::Negative
+        } else {
+            Self::Positive
+        }
+    }
+}
+
+/// What a gamepad did, without the device it came from. Every pad counts as the same one.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PadEvent {
+    Button(Button, ElementState),
+    /// -1.0 to 1.0 for sticks, 0.0 to 1.0 for triggers, before the deadzone
+    Axis(Axis, f32),
+}
+
+/// Somewhere gamepad events come from. Normally the real pads through gilrs, a
+/// [`SimulatedPad`] when there's nobody holding one.
+pub trait PadSource {
+    fn next_pad_event(&mut self) -> Option<PadEvent>;
+}
+
+impl PadSource for Gilrs {
+    fn next_pad_event(&mut self) -> Option<PadEvent> {
+        while let Some(gilrs::Event { id, event, .. }) = self.next_event() {
+            match event {
+                EventType::ButtonPressed(button, _) => {
+                    return Some(PadEvent::Button(button, ElementState::Pressed))
+                }
+                EventType::ButtonReleased(button, _) => {
+                    return Some(PadEvent::Button(button, ElementState::Released))
+                }
+                // analog triggers come as buttons too
+                EventType::ButtonChanged(Button::LeftTrigger2, value, _) => {
+                    return Some(PadEvent::Axis(Axis::LeftZ, value))
+                }
+                EventType::ButtonChanged(Button::RightTrigger2, value, _) => {
+                    return Some(PadEvent::Axis(Axis::RightZ, value))
+                }
+                EventType::AxisChanged(axis, value, _) => return Some(PadEvent::Axis(axis, value)),
+                EventType::Connected => info!("{} plugged in", self.gamepad(id).name()),
+                EventType::Disconnected => info!("{} unplugged", self.gamepad(id).name()),
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+/// A gamepad that does whatever it's told, everything queued comes out on the next poll.
+#[derive(Debug, Default)]
+pub struct SimulatedPad {
+    pub events: VecDeque<PadEvent>,
+}
+impl SimulatedPad {
+    pub fn press(&mut self, button: Button) {
+        self.events
+            .push_back(PadEvent::Button(button, ElementState::Pressed));
+    }
+    pub fn release(&mut self, button: Button) {
+        self.events
+            .push_back(PadEvent::Button(button, ElementState::Released));
+    }
+    pub fn tilt(&mut self, axis: Axis, value: f32) {
+        self.events.push_back(PadEvent::Axis(axis, value));
+    }
+}
+impl PadSource for SimulatedPad {
+    fn next_pad_event(&mut self) -> Option<PadEvent> {
+        self.events.pop_front()
+    }
+}
+
+/// The real gamepads, or nothing if gilrs can't get at them.
+pub fn connect_gamepads() -> Option<Box<dyn PadSource>> {
+    match Gilrs::new() {
+        Ok(gilrs) => {
+            for (_, gamepad) in gilrs.gamepads() {
+                info!("found {}", gamepad.name());
+            }
+            Some(Box::new(gilrs))
+        }
+        Err(e) => {
+            warn!("no gamepads: {e}");
+            None
+        }
+    }
+}
+
+/// Squash everything inside `deadzone` to 0 and stretch the rest so it still reaches 1.
+pub fn apply_deadzone(value: f32, deadzone: f32) -> f32 {
+    if value.abs() <= deadzone {
+        0.0
+    } else {
+        value.signum() * (value.abs() - deadzone) / (1.0 - deadzone)
+    }
+}
+
+impl InputStatus {
+    /// The deadzoned value of `axis`, 0 when it hasn't moved.
+    pub fn axis(&self, axis: Axis) -> f32 {
+        self.axes.get(&axis).copied().unwrap_or_default()
+    }
+    /// A stick as a vector, up being positive y.
+    pub fn stick(&self, x: Axis, y: Axis) -> glam::Vec2 {
+        glam::vec2(self.axis(x), self.axis(y))
+    }
+
+    /// Record what a pad did. Returns the button-like inputs it amounted to, an axis crossing
+    /// [`AXIS_PRESS_THRESHOLD`] being a press or a release of that direction.
+    pub fn feed_pad(
+        &mut self,
+        event: PadEvent,
+        deadzone: f32,
+        now: Instant,
+    ) -> Vec<(AcceptedInput, ElementState)> {
+        match event {
+            PadEvent::Button(button, state) => {
+                self.feed_at(AcceptedInput::GB(button), state, now);
+                vec![(AcceptedInput::GB(button), state)]
+            }
+            PadEvent::Axis(axis, value) => {
+                let value = apply_deadzone(value, deadzone);
+                let before = self.axes.insert(axis, value).unwrap_or_default();
+                let mut changes = vec![];
+                for direction in [AxisDirection::Positive, AxisDirection::Negative] {
+                    let pushed = |v: f32| {
+                        AxisDirection::of(v) == direction && v.abs() >= AXIS_PRESS_THRESHOLD
+                    };
+                    let state = match (pushed(before), pushed(value)) {
+                        (false, true) => ElementState::Pressed,
+                        (true, false) => ElementState::Released,
+                        _ => continue,
+                    };
+                    let input = AcceptedInput::GA(axis, direction);
+                    self.set_at(input, state, now);
+                    changes.push((input, state));
+                }
+                changes
+            }
+        }
+    }
+}
+
+/// Feed everything the pads did since the last frame into the input status, unless a rebinding
+/// is waiting for it. While a recording plays the pads get emptied and ignored.
+pub fn poll_gamepad<InputContextEnum: InputContext>(
+    state: &mut GameProgrammeState<InputContextEnum>,
+    deadzone: f32,
+) {
+    while let Some(event) = state.gamepad.as_mut().and_then(|g| g.next_pad_event()) {
+        if state.replayer.is_some() {
+            continue;
+        }
+        state.record_input(InputEvent::Pad(event));
+        let changes = state.input_status.feed_pad(event, deadzone, Instant::now());
+        for (input, pressed) in changes {
+            if capture_rebinding(
+                &mut state.keybindings,
+                &mut state.rebinding,
+                input,
+                pressed,
+                state.input_status.modifiers,
+            ) {
+                state.input_status.forget(&input);
+            }
+        }
+    }
+}
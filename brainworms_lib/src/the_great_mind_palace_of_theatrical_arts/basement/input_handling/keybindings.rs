@@ -0,0 +1,313 @@
+// which inputs do what, changeable by the player and kept in the settings file
+use std::collections::{BTreeMap, HashMap};
+
+use brainworms_arson::egui::{self, Color32};
+use log::{info, warn};
+use serde::{de::DeserializeOwned, Serialize};
+use winit::{
+    event::ElementState,
+    keyboard::{KeyCode, ModifiersState},
+};
+
+use super::{AcceptedInput, InputContext, Press};
+
+/// Every action with the inputs that trigger it, any of them will do.
+/// Two actions of the same input context sharing an input is a conflict, across contexts it's fine
+/// since only one context is listened to at a time.
+#[derive(Debug, Clone)]
+pub struct KeyBindings<InputContextEnum: InputContext> {
+    bindings: HashMap<InputContextEnum, Vec<AcceptedInput>>,
+}
+impl<InputContextEnum: InputContext> Default for KeyBindings<InputContextEnum> {
+    fn default() -> Self {
+        Self {
+            bindings: HashMap::new(),
+        }
+    }
+}
+impl<InputContextEnum: InputContext, const N: usize> From<[(InputContextEnum, AcceptedInput); N]>
+    for KeyBindings<InputContextEnum>
+{
+    fn from(bindings: [(InputContextEnum, AcceptedInput); N]) -> Self {
+        let mut key_bindings = Self::default();
+        for (action, input) in bindings {
+            key_bindings.insert(action, input);
+        }
+        key_bindings
+    }
+}
+
+/// Waiting for the player to press whatever should trigger `action` next.
+#[derive(Debug, Clone)]
+pub struct Rebinding<InputContextEnum> {
+    pub action: InputContextEnum,
+    /// which of the action's inputs gets replaced, `None` adds another one
+    pub replacing: Option<usize>,
+}
+
+impl<InputContextEnum: InputContext> KeyBindings<InputContextEnum> {
+    pub fn inputs(&self, action: &InputContextEnum) -> &[AcceptedInput] {
+        self.bindings.get(action).map_or(&[], Vec::as_slice)
+    }
+    pub fn is_bound(&self, action: &InputContextEnum) -> bool {
+        self.bindings.contains_key(action)
+    }
+    /// Add another input for `action`.
+    pub fn insert(&mut self, action: InputContextEnum, input: AcceptedInput) {
+        let inputs = self.bindings.entry(action).or_default();
+        if !inputs.contains(&input) {
+            inputs.push(input);
+        }
+    }
+    /// Forget every input of `action`. It stays known, so defaults won't bring them back.
+    pub fn unbind(&mut self, action: &InputContextEnum) {
+        if let Some(inputs) = self.bindings.get_mut(action) {
+            inputs.clear();
+        }
+    }
+    /// Take on the bindings for every action that doesn't have any yet, for playables to hand in
+    /// their defaults without trampling over what the player picked.
+    pub fn bind_defaults(&mut self, defaults: KeyBindings<InputContextEnum>) {
+        for (action, inputs) in defaults.bindings {
+            self.bindings.entry(action).or_insert(inputs);
+        }
+    }
+    /// The other actions in the same input context as `action` that `input` is bound to.
+    pub fn conflicts_with(
+        &self,
+        action: &InputContextEnum,
+        input: &AcceptedInput,
+    ) -> Vec<&InputContextEnum> {
+        self.bindings
+            .iter()
+            .filter(|(other, inputs)| {
+                *other != action && other.context() == action.context() && inputs.contains(input)
+            })
+            .map(|(other, _)| other)
+            .collect()
+    }
+    /// Every input bound to more than one action of the same input context.
+    pub fn conflicts(&self) -> Vec<(AcceptedInput, Vec<&InputContextEnum>)> {
+        let mut seen: HashMap<_, Vec<&InputContextEnum>> = HashMap::new();
+        for (action, inputs) in &self.bindings {
+            for input in inputs {
+                seen.entry((action.context(), *input))
+                    .or_default()
+                    .push(action);
+            }
+        }
+        seen.into_iter()
+            .filter(|(_, actions)| actions.len() > 1)
+            .map(|((_, input), actions)| (input, actions))
+            .collect()
+    }
+    /// Finish `rebinding` with `input`. Conflicts get logged, not refused, the player sees them
+    /// in red in the bindings list.
+    pub fn rebind(&mut self, rebinding: Rebinding<InputContextEnum>, input: AcceptedInput) {
+        let conflicts = self.conflicts_with(&rebinding.action, &input);
+        if !conflicts.is_empty() {
+            warn!(
+                "{input:?} for {:?} is also bound to {conflicts:?}",
+                rebinding.action
+            );
+        }
+        let inputs = self.bindings.entry(rebinding.action).or_default();
+        match rebinding.replacing {
+            Some(i) if i < inputs.len() => inputs[i] = input,
+            _ if inputs.contains(&input) => {}
+            _ => inputs.push(input),
+        }
+    }
+
+    /// Bindings from the settings file replace whatever the actions had, actions or inputs that
+    /// don't parse get logged and skipped.
+    pub fn load_from(&mut self, saved: &BTreeMap<String, BTreeMap<String, Vec<String>>>) {
+        for (context, actions) in saved {
+            for (action_name, inputs) in actions {
+                let Some(action) = join_action::<InputContextEnum>(context, action_name) else {
+                    warn!("no {context} action called {action_name:?} to bind");
+                    continue;
+                };
+                let inputs = inputs
+                    .iter()
+                    .filter_map(|input| match ron::from_str(input) {
+                        Ok(input) => Some(input),
+                        Err(e) => {
+                            warn!("can't bind {input:?} to {context} {action_name}: {e}");
+                            None
+                        }
+                    })
+                    .collect();
+                self.bindings.insert(action, inputs);
+            }
+        }
+        info!("loaded bindings for {} actions", self.bindings.len());
+    }
+    /// Write every binding into the settings file's bindings, leaving alone the ones for actions
+    /// this programme hasn't heard of yet.
+    pub fn save_to(&self, saved: &mut BTreeMap<String, BTreeMap<String, Vec<String>>>) {
+        for (action, inputs) in &self.bindings {
+            let Some((context, action_name)) = split_action(action) else {
+                continue;
+            };
+            let inputs = inputs
+                .iter()
+                .filter_map(|input| ron::to_string(input).ok())
+                .collect();
+            saved
+                .entry(context)
+                .or_default()
+                .insert(action_name, inputs);
+        }
+    }
+
+    /// Every action sorted by context and name, for showing to the player.
+    fn sorted(&self) -> Vec<((String, String), &InputContextEnum)> {
+        let mut actions: Vec<_> = self
+            .bindings
+            .keys()
+            .filter_map(|action| Some((split_action(action)?, action)))
+            .collect();
+        actions.sort_by(|a, b| a.0.cmp(&b.0));
+        actions
+    }
+}
+
+// `DebugInputContext(Sprint)` goes in the file as `DebugInputContext` and `Sprint`
+fn split_action<T: Serialize>(action: &T) -> Option<(String, String)> {
+    let text = ron::to_string(action).ok()?;
+    match text.split_once('(') {
+        Some((context, name)) => Some((context.to_owned(), name.strip_suffix(')')?.to_owned())),
+        None => Some((text, String::new())),
+    }
+}
+fn join_action<T: DeserializeOwned>(context: &str, name: &str) -> Option<T> {
+    let text = if name.is_empty() {
+        context.to_owned()
+    } else {
+        format!("{context}({name})")
+    };
+    ron::from_str(&text).ok()
+}
+
+/// What the player pressed while a rebinding was waiting for it. Escape gives up on rebinding,
+/// releases are ignored so the click on the rebind button doesn't bind itself.
+/// Holding modifiers makes a chord, a modifier on its own gets bound when it's let go of without
+/// anything else pressed in the meantime. Returns whether the input was used up.
+pub fn capture_rebinding<InputContextEnum: InputContext>(
+    keybindings: &mut KeyBindings<InputContextEnum>,
+    rebinding: &mut Option<Rebinding<InputContextEnum>>,
+    input: AcceptedInput,
+    state: ElementState,
+    modifiers: ModifiersState,
+) -> bool {
+    if rebinding.is_none() {
+        return false;
+    }
+    let press = Press::of(input);
+    let modifier = press.is_some_and(|p| p.is_modifier());
+    let bound = match (state, press) {
+        (ElementState::Pressed, _) if input == AcceptedInput::KB(KeyCode::Escape) => None,
+        // might be the start of a chord, wait and see
+        (ElementState::Pressed, Some(_)) if modifier => return true,
+        (ElementState::Pressed, Some(press)) if !modifiers.is_empty() => {
+            Some(AcceptedInput::Chord(modifiers, press))
+        }
+        (ElementState::Pressed, _) => Some(input),
+        (ElementState::Released, Some(_)) if modifier => Some(input),
+        // a wheel notch only ever comes as released
+        (ElementState::Released, None) if matches!(input, AcceptedInput::Wheel(_)) => Some(input),
+        (ElementState::Released, _) => return false,
+    };
+    let waiting = rebinding.take().unwrap();
+    if let Some(input) = bound {
+        keybindings.rebind(waiting, input);
+    }
+    true
+}
+
+/// The list of actions and their inputs, each one a button that waits for the next key or mouse
+/// button to replace it.
+pub fn keybindings_ui<InputContextEnum: InputContext>(
+    ui: &mut egui::Ui,
+    keybindings: &mut KeyBindings<InputContextEnum>,
+    rebinding: &mut Option<Rebinding<InputContextEnum>>,
+) {
+    let mut unbind = None;
+    let mut retype = None;
+    egui::Grid::new("keybindings").striped(true).show(ui, |ui| {
+        for ((context, name), action) in keybindings.sorted() {
+            ui.label(context);
+            ui.label(name);
+            ui.horizontal(|ui| {
+                let inputs = keybindings.inputs(action);
+                for (i, input) in inputs.iter().enumerate() {
+                    let waiting = rebinding
+                        .as_ref()
+                        .is_some_and(|r| &r.action == action && r.replacing == Some(i));
+                    let conflicts = keybindings.conflicts_with(action, input);
+                    let text = if waiting {
+                        egui::RichText::new("press something...")
+                    } else if conflicts.is_empty() {
+                        egui::RichText::new(input.to_string())
+                    } else {
+                        egui::RichText::new(input.to_string()).color(Color32::RED)
+                    };
+                    let button = ui.button(text);
+                    let button = if conflicts.is_empty() {
+                        button
+                    } else {
+                        button.on_hover_text(format!("also bound to {conflicts:?}"))
+                    };
+                    if button.clicked() {
+                        *rebinding = Some(Rebinding {
+                            action: action.clone(),
+                            replacing: Some(i),
+                        });
+                    }
+                    // double clicks and long presses can't be pressed in, they're picked here
+                    if let Some(press) = Press::of(*input).or(input.press()) {
+                        button.context_menu(|ui| {
+                            for (label, variant) in [
+                                ("press", AcceptedInput::from(press)),
+                                ("double click", AcceptedInput::DoubleClick(press)),
+                                ("long press", AcceptedInput::LongPress(press)),
+                            ] {
+                                if ui.button(label).clicked() {
+                                    retype = Some((action.clone(), i, variant));
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                    }
+                }
+                let adding = rebinding
+                    .as_ref()
+                    .is_some_and(|r| &r.action == action && r.replacing.is_none());
+                let add = if adding { "press something..." } else { "+" };
+                if ui.button(add).clicked() {
+                    *rebinding = Some(Rebinding {
+                        action: action.clone(),
+                        replacing: None,
+                    });
+                }
+                if !inputs.is_empty() && ui.button("clear").clicked() {
+                    unbind = Some(action.clone());
+                }
+            });
+            ui.end_row();
+        }
+    });
+    if let Some(action) = unbind {
+        keybindings.unbind(&action);
+    }
+    if let Some((action, i, input)) = retype {
+        keybindings.rebind(
+            Rebinding {
+                action,
+                replacing: Some(i),
+            },
+            input,
+        );
+    }
+}
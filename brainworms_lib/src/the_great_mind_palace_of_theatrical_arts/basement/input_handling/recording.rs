@@ -0,0 +1,273 @@
+// input tapes: everything the player did, frame by frame, to play back later the same way
+use std::{
+    collections::VecDeque,
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use log::{info, warn};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use thiserror::Error;
+use winit::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    event::ElementState,
+    keyboard::ModifiersState,
+};
+
+use crate::{
+    theater::play::{
+        scene::{stage3d::mouse_look, CamInfo},
+        Playable,
+    },
+    GameProgramme, GameProgrammeSettings, GameProgrammeState,
+};
+
+use super::{gamepad::PadEvent, text_input::TextEdit, AcceptedInput, InputContext, WheelDirection};
+
+/// Bump this whenever [`InputRecording`] changes shape.
+pub const RECORDING_VERSION: u32 = 1;
+
+/// Everything that goes into [`super::InputStatus`] or moves the camera, as it came in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum InputEvent {
+    Button(AcceptedInput, ElementState),
+    Wheel(WheelDirection),
+    Modifiers(ModifiersState),
+    CursorMoved(f64, f64),
+    /// only ever recorded while the mouse is grabbed
+    MouseMotion(f64, f64),
+    Resized(u32, u32),
+    Pad(PadEvent),
+    /// what the keyboard did while typing
+    Text(TextEdit),
+}
+
+/// The settings that change what the same input does.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct InputFeel {
+    pub absolute_mouse: bool,
+    pub mouse_sensitivity: f32,
+    pub deadzone: f32,
+}
+impl InputFeel {
+    pub fn of(settings: &GameProgrammeSettings) -> Self {
+        Self {
+            absolute_mouse: settings.absolute_mouse,
+            mouse_sensitivity: settings.mouse_sensitivity,
+            deadzone: settings.deadzone,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    /// how long the frame was, what the playable moved things by
+    pub dt: Duration,
+    pub events: Vec<InputEvent>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputRecording {
+    pub version: u32,
+    pub feel: InputFeel,
+    pub frames: Vec<RecordedFrame>,
+}
+
+#[derive(Debug, Error)]
+pub enum RecordingError {
+    #[error("can't get at the input recording: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("input recording is broken: {0}")]
+    Malformed(String),
+    #[error("input recording is from version {0}, this programme reads {RECORDING_VERSION}")]
+    WrongVersion(u32),
+}
+
+impl InputRecording {
+    pub fn load(path: &Path) -> Result<Self, RecordingError> {
+        let text = fs::read_to_string(path)?;
+        let recording: Self =
+            ron::from_str(&text).map_err(|e| RecordingError::Malformed(e.to_string()))?;
+        if recording.version != RECORDING_VERSION {
+            return Err(RecordingError::WrongVersion(recording.version));
+        }
+        Ok(recording)
+    }
+    pub fn save(&self, path: &Path) -> Result<(), RecordingError> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| RecordingError::Malformed(e.to_string()))?;
+        fs::write(path, text)?;
+        Ok(())
+    }
+}
+
+/// Keeps what came in during the current frame until the frame is over.
+#[derive(Debug)]
+pub struct InputRecorder {
+    recording: InputRecording,
+    current: Vec<InputEvent>,
+}
+impl InputRecorder {
+    pub fn new(feel: InputFeel) -> Self {
+        Self {
+            recording: InputRecording {
+                version: RECORDING_VERSION,
+                feel,
+                frames: vec![],
+            },
+            current: vec![],
+        }
+    }
+    pub fn finish(mut self) -> InputRecording {
+        if !self.current.is_empty() {
+            self.recording.frames.push(RecordedFrame {
+                dt: Duration::ZERO,
+                events: self.current,
+            });
+        }
+        self.recording
+    }
+}
+
+/// Plays back a recording on its own clock, so timing like double clicks and long presses comes
+/// out the same however fast the frames go now.
+#[derive(Debug)]
+pub struct InputReplayer {
+    feel: InputFeel,
+    frames: VecDeque<RecordedFrame>,
+    clock: Instant,
+}
+impl InputReplayer {
+    pub fn new(recording: InputRecording) -> Self {
+        Self {
+            feel: recording.feel,
+            frames: recording.frames.into(),
+            clock: Instant::now(),
+        }
+    }
+}
+
+impl<InputContextEnum: InputContext> GameProgrammeState<InputContextEnum> {
+    /// Everything the player does comes in through here. Ignored while a recording is playing.
+    pub fn take_input(&mut self, settings: &GameProgrammeSettings, event: InputEvent) {
+        if self.replayer.is_some() {
+            return;
+        }
+        if matches!(event, InputEvent::MouseMotion(..))
+            && !self.grabber.as_ref().is_some_and(|g| g.grabbed())
+        {
+            return;
+        }
+        self.record_input(event.clone());
+        self.apply_input(InputFeel::of(settings), event, Instant::now());
+    }
+    /// Put `event` on the tape, if there is one.
+    pub fn record_input(&mut self, event: InputEvent) {
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.current.push(event);
+        }
+    }
+    fn apply_input(&mut self, feel: InputFeel, event: InputEvent, now: Instant) {
+        let input_status = &mut self.input_status;
+        match event {
+            InputEvent::Button(input, state) => input_status.feed_at(input, state, now),
+            InputEvent::Wheel(direction) => input_status.feed_wheel_direction(direction),
+            InputEvent::Modifiers(modifiers) => input_status.modifiers = modifiers,
+            InputEvent::CursorMoved(x, y) => {
+                input_status.mouse_physical_poz = PhysicalPosition::new(x, y)
+            }
+            InputEvent::MouseMotion(dx, dy) => {
+                mouse_look(feel.absolute_mouse, feel.mouse_sensitivity, self, dx, dy)
+            }
+            InputEvent::Resized(width, height) => {
+                input_status.window_size = PhysicalSize::new(width, height)
+            }
+            InputEvent::Pad(event) => {
+                input_status.feed_pad(event, feel.deadzone, now);
+            }
+            InputEvent::Text(edit) => {
+                if let Some(text_input) = self.text_input.as_mut() {
+                    text_input.apply(edit);
+                }
+            }
+        }
+    }
+
+    /// Start a frame: close the recorded one, or feed in the next one from the recording.
+    /// Sets [`GameProgrammeState::frame_delta`] for the playables to move things by.
+    pub fn start_input_frame(&mut self) {
+        let live_dt = self
+            .last_update
+            .map_or(Duration::ZERO, |last_update| last_update.elapsed());
+        if let Some(recorder) = self.recorder.as_mut() {
+            let events = std::mem::take(&mut recorder.current);
+            recorder.recording.frames.push(RecordedFrame {
+                dt: live_dt,
+                events,
+            });
+        }
+        let Some(replayer) = self.replayer.as_mut() else {
+            self.frame_delta = live_dt;
+            self.input_status.update_long_presses(Instant::now());
+            return;
+        };
+        let Some(frame) = replayer.frames.pop_front() else {
+            info!("input replay is over, back to live input");
+            self.replayer = None;
+            if let Some(window) = &self.window {
+                self.input_status.window_size = window.inner_size();
+            }
+            self.frame_delta = live_dt;
+            self.input_status.update_long_presses(Instant::now());
+            return;
+        };
+        replayer.clock += frame.dt;
+        let (feel, now) = (replayer.feel, replayer.clock);
+        self.frame_delta = frame.dt;
+        for event in frame.events {
+            self.apply_input(feel, event, now);
+        }
+        self.input_status.update_long_presses(now);
+    }
+
+    /// Stop recording and write the tape to `path`.
+    pub fn save_recording(&mut self, path: &Path) {
+        let Some(recorder) = self.recorder.take() else {
+            return;
+        };
+        match recorder.finish().save(path) {
+            Ok(()) => info!("input recorded to {}", path.display()),
+            Err(e) => warn!("{e}"),
+        }
+    }
+}
+
+impl<
+        PlayablesEnum: Playable<InputContextEnum, UserData> + 'static,
+        InputContextEnum: InputContext + 'static,
+        UserData: Default + Serialize + DeserializeOwned + 'static,
+    > GameProgramme<PlayablesEnum, InputContextEnum, UserData>
+{
+    /// Drive the current playable through `recording` with no window, renderer or event loop,
+    /// through the same [`play_frame`](Self::play_frame) as `AboutToWait`. Stage directions wait
+    /// for a window to be followed. Returns where the camera was after every frame, for checking
+    /// camera paths and whatever picking moved it to.
+    pub fn replay_headless(&mut self, recording: InputRecording) -> Vec<Option<CamInfo>> {
+        self.state.recorder = None;
+        self.state.replayer = Some(InputReplayer::new(recording));
+        let mut camera_path = vec![];
+        while self
+            .state
+            .replayer
+            .as_ref()
+            .is_some_and(|r| !r.frames.is_empty())
+        {
+            self.play_frame(None);
+            self.state.input_status.end_frame();
+            camera_path.push(self.state.cur_camera.as_ref().map(|c| c.info.clone()));
+        }
+        self.state.replayer = None;
+        camera_path
+    }
+}
@@ -0,0 +1,218 @@
+// typing into in-game terminals: a line at a time with editing, history and the IME, none of it
+// going anywhere near the keybindings while it's on
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+use winit::{
+    event::{Ime, KeyEvent},
+    keyboard::{KeyCode, ModifiersState, PhysicalKey},
+};
+
+use crate::GameProgrammeState;
+
+use super::InputContext;
+
+/// How many submitted lines are kept to go back through.
+pub const HISTORY_LENGTH: usize = 100;
+
+/// One thing done to the line being typed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TextEdit {
+    /// typed or committed by the IME, goes in at the cursor
+    Insert(String),
+    /// what the IME is still composing and where its cursor is in it, in bytes. Empty when it's
+    /// done composing
+    Preedit(String, Option<(usize, usize)>),
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Home,
+    End,
+    /// back through the history
+    Older,
+    Newer,
+    Submit,
+    Cancel,
+}
+impl TextEdit {
+    /// What a key going down does to the line. Keys typed with ctrl, alt or super held are
+    /// shortcuts and don't type anything.
+    pub fn of_key(event: &KeyEvent, modifiers: ModifiersState) -> Option<Self> {
+        if !event.state.is_pressed() {
+            return None;
+        }
+        let key_code = match event.physical_key {
+            PhysicalKey::Code(key_code) => Some(key_code),
+            PhysicalKey::Unidentified(_) => None,
+        };
+        Some(match key_code {
+            Some(KeyCode::Backspace) => Self::Backspace,
+            Some(KeyCode::Delete) => Self::Delete,
+            Some(KeyCode::ArrowLeft) => Self::Left,
+            Some(KeyCode::ArrowRight) => Self::Right,
+            Some(KeyCode::Home) => Self::Home,
+            Some(KeyCode::End) => Self::End,
+            Some(KeyCode::ArrowUp) => Self::Older,
+            Some(KeyCode::ArrowDown) => Self::Newer,
+            Some(KeyCode::Enter | KeyCode::NumpadEnter) => Self::Submit,
+            Some(KeyCode::Escape) => Self::Cancel,
+            _ if modifiers.control_key() || modifiers.alt_key() || modifiers.super_key() => {
+                return None
+            }
+            _ => Self::Insert(event.text.as_ref()?.to_string()),
+        })
+    }
+    pub fn of_ime(ime: &Ime) -> Option<Self> {
+        match ime {
+            Ime::Preedit(text, cursor) => Some(Self::Preedit(text.clone(), *cursor)),
+            Ime::Commit(text) => Some(Self::Insert(text.clone())),
+            // whatever was being composed goes with it
+            Ime::Disabled => Some(Self::Preedit(String::new(), None)),
+            Ime::Enabled => None,
+        }
+    }
+}
+
+/// The line being typed, and the ones typed before it.
+#[derive(Debug, Clone, Default)]
+pub struct TextInput {
+    line: String,
+    /// in chars, not bytes
+    cursor: usize,
+    preedit: Option<(String, Option<(usize, usize)>)>,
+    /// oldest first
+    history: VecDeque<String>,
+    /// which line of the history is showing, and what was being typed before going back
+    browsing: Option<(usize, String)>,
+    submitted: VecDeque<String>,
+    /// escape was pressed, it's up to whoever is listening to stop typing
+    pub cancelled: bool,
+}
+
+impl TextInput {
+    /// An empty line that can go back through `history`, oldest first.
+    pub fn with_history(history: impl IntoIterator<Item = String>) -> Self {
+        let mut history: VecDeque<_> = history.into_iter().collect();
+        while history.len() > HISTORY_LENGTH {
+            history.pop_front();
+        }
+        Self {
+            history,
+            ..Default::default()
+        }
+    }
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+    /// Where the cursor is in [`TextInput::line`], in chars.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+    pub fn preedit(&self) -> Option<&(String, Option<(usize, usize)>)> {
+        self.preedit.as_ref()
+    }
+    pub fn history(&self) -> impl Iterator<Item = &String> {
+        self.history.iter()
+    }
+    /// The next line submitted with enter, oldest first.
+    pub fn take_submitted(&mut self) -> Option<String> {
+        self.submitted.pop_front()
+    }
+
+    pub fn apply(&mut self, edit: TextEdit) {
+        let len = self.line.chars().count();
+        match edit {
+            TextEdit::Insert(text) => {
+                let text: String = text.chars().filter(|c| !c.is_control()).collect();
+                let at = self.byte_at(self.cursor);
+                self.line.insert_str(at, &text);
+                self.cursor += text.chars().count();
+                self.browsing = None;
+            }
+            TextEdit::Preedit(text, cursor) => {
+                self.preedit = (!text.is_empty()).then_some((text, cursor));
+            }
+            TextEdit::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.line.remove(self.byte_at(self.cursor));
+            }
+            TextEdit::Delete if self.cursor < len => {
+                self.line.remove(self.byte_at(self.cursor));
+            }
+            TextEdit::Backspace | TextEdit::Delete => {}
+            TextEdit::Left => self.cursor = self.cursor.saturating_sub(1),
+            TextEdit::Right => self.cursor = (self.cursor + 1).min(len),
+            TextEdit::Home => self.cursor = 0,
+            TextEdit::End => self.cursor = len,
+            TextEdit::Older => {
+                let (showing, typing) = match self.browsing.take() {
+                    Some(browsing) => browsing,
+                    None => (self.history.len(), self.line.clone()),
+                };
+                let showing = showing.saturating_sub(1);
+                if let Some(line) = self.history.get(showing) {
+                    self.show(line.clone());
+                    self.browsing = Some((showing, typing));
+                }
+            }
+            TextEdit::Newer => {
+                let Some((showing, typing)) = self.browsing.take() else {
+                    return;
+                };
+                match self.history.get(showing + 1) {
+                    Some(line) => {
+                        self.show(line.clone());
+                        self.browsing = Some((showing + 1, typing));
+                    }
+                    None => self.show(typing),
+                }
+            }
+            TextEdit::Submit => {
+                let line = std::mem::take(&mut self.line);
+                self.cursor = 0;
+                self.browsing = None;
+                if !line.trim().is_empty() && self.history.back() != Some(&line) {
+                    self.history.push_back(line.clone());
+                    if self.history.len() > HISTORY_LENGTH {
+                        self.history.pop_front();
+                    }
+                }
+                self.submitted.push_back(line);
+            }
+            TextEdit::Cancel => self.cancelled = true,
+        }
+    }
+
+    fn show(&mut self, line: String) {
+        self.cursor = line.chars().count();
+        self.line = line;
+    }
+    fn byte_at(&self, cursor: usize) -> usize {
+        self.line
+            .char_indices()
+            .nth(cursor)
+            .map_or(self.line.len(), |(i, _)| i)
+    }
+}
+
+impl<InputContextEnum: InputContext> GameProgrammeState<InputContextEnum> {
+    /// Send key presses and the IME to `text_input` instead of the keybindings, until
+    /// [`GameProgrammeState::stop_typing`]. Mouse and gamepads carry on as usual.
+    pub fn start_typing(&mut self, text_input: TextInput) {
+        if let Some(window) = &self.window {
+            window.set_ime_allowed(true);
+        }
+        self.text_input = Some(text_input);
+    }
+    /// Give the keyboard back to the keybindings, handing over what was typed.
+    pub fn stop_typing(&mut self) -> Option<TextInput> {
+        if let Some(window) = &self.window {
+            window.set_ime_allowed(false);
+        }
+        self.text_input.take()
+    }
+    pub fn is_typing(&self) -> bool {
+        self.text_input.is_some()
+    }
+}
@@ -0,0 +1,290 @@
+// the settings the player picked, kept between runs. command line flags still win over these
+use std::{collections::BTreeMap, fs, path::Path};
+
+use brainworms_arson::egui::{self, Context};
+use brainworms_farting_noises::{AudioCommand, SoundGroup};
+use log::{info, warn};
+use rend3::types::SampleCount;
+use serde::{Deserialize, Serialize};
+
+use crate::GameProgrammeState;
+
+use super::{
+    cla::{extract_backend, extract_profile, extract_vsync, GameProgrammeSettings},
+    input_handling::{keybindings::keybindings_ui, InputContext},
+};
+
+pub const SETTINGS_FILE: &str = "settings.toml";
+
+/// What's in [`SETTINGS_FILE`]. Everything is optional, whatever is missing falls back to the
+/// command line or the defaults. Backend, profile, vsync and msaa are written like their flags.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SettingsFile {
+    pub backend: Option<String>,
+    pub device: Option<String>,
+    pub profile: Option<String>,
+    pub vsync: Option<String>,
+    pub msaa: Option<u32>,
+    pub walk_speed: Option<f32>,
+    pub run_speed: Option<f32>,
+    pub mouse_sensitivity: Option<f32>,
+    pub deadzone: Option<f32>,
+    pub bgm_volume: Option<f32>,
+    pub sfx_volume: Option<f32>,
+    /// action to inputs, per input context, for playables to bind their inputs with
+    pub keybindings: BTreeMap<String, BTreeMap<String, Vec<String>>>,
+}
+
+impl SettingsFile {
+    /// A broken or missing file is the same as an empty one, but a broken one gets logged.
+    pub fn load() -> Self {
+        Self::load_from(Path::new(SETTINGS_FILE))
+    }
+    pub fn load_from(path: &Path) -> Self {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) => {
+                info!("no settings from {}: {e}", path.display());
+                return Self::default();
+            }
+        };
+        match toml::from_str(&text) {
+            Ok(settings_file) => settings_file,
+            Err(e) => {
+                warn!("ignoring {}: {e}", path.display());
+                Self::default()
+            }
+        }
+    }
+    pub fn save(&self) {
+        self.save_to(Path::new(SETTINGS_FILE));
+    }
+    pub fn save_to(&self, path: &Path) {
+        let text = match toml::to_string_pretty(self) {
+            Ok(text) => text,
+            Err(e) => {
+                warn!("couldn't write the settings down: {e}");
+                return;
+            }
+        };
+        match fs::write(path, text) {
+            Ok(()) => info!("settings saved to {}", path.display()),
+            Err(e) => warn!("couldn't save settings to {}: {e}", path.display()),
+        }
+    }
+}
+
+/// Parse a value from the settings file with the same function as its flag, logging what's wrong
+/// with it instead of quitting like a bad flag does.
+pub(crate) fn from_settings_file<T>(
+    field: &str,
+    value: Option<&str>,
+    extract: fn(&str) -> Result<T, &'static str>,
+) -> Option<T> {
+    let value = value?;
+    extract(value)
+        .map_err(|e| warn!("{SETTINGS_FILE}: ignoring {field} = {value:?}: {e}"))
+        .ok()
+}
+
+const BACKENDS: [(&str, &str); 5] = [
+    ("", "automatic"),
+    ("vk", "vulkan"),
+    ("dx12", "directx 12"),
+    ("metal", "metal"),
+    ("gl", "opengl"),
+];
+const PROFILES: [(&str, &str); 3] = [
+    ("", "automatic"),
+    ("cpu", "cpu driven"),
+    ("gpu", "gpu driven"),
+];
+const VSYNCS: [(&str, &str); 3] = [("immediate", "off"), ("fifo", "on"), ("mailbox", "fast")];
+const MSAAS: [(u32, &str); 2] = [(1, "off"), (4, "4x")];
+
+fn pick<T: PartialEq + Copy>(
+    ui: &mut egui::Ui,
+    label: &str,
+    current: &mut T,
+    choices: &[(T, &str)],
+) -> bool {
+    let selected = choices
+        .iter()
+        .find(|(value, _)| value == current)
+        .map_or("?", |(_, name)| name);
+    let mut changed = false;
+    egui::ComboBox::from_label(label)
+        .selected_text(selected)
+        .show_ui(ui, |ui| {
+            for (value, name) in choices {
+                changed |= ui.selectable_value(current, *value, *name).changed();
+            }
+        });
+    changed
+}
+
+/// [`pick`] for a setting that's saved as its flag, `""` being no flag at all.
+fn pick_flag(
+    ui: &mut egui::Ui,
+    label: &str,
+    saved: &mut Option<String>,
+    choices: &[(&'static str, &str)],
+) {
+    let mut flag = choices
+        .iter()
+        .map(|(flag, _)| *flag)
+        .find(|flag| Some(*flag) == saved.as_deref())
+        .unwrap_or(choices[0].0);
+    if pick(ui, label, &mut flag, choices) {
+        *saved = (!flag.is_empty()).then(|| flag.to_owned());
+    }
+}
+
+fn restart_required(ui: &mut egui::Ui, required: bool) {
+    if required {
+        ui.label("(needs a restart)");
+    }
+}
+
+/// The settings window. Vsync, volumes, speeds, mouse sensitivity and bindings apply straight
+/// away, the rest goes in the settings file and waits for the next start.
+pub fn show_settings_panel<InputContextEnum: InputContext>(
+    egui_ctx: &Context,
+    settings: &mut GameProgrammeSettings,
+    state: &mut GameProgrammeState<InputContextEnum>,
+) {
+    let mut save = false;
+    let orchestra = state.orchestra.as_deref();
+    egui::Window::new("settings")
+        .open(&mut state.settings_panel_open)
+        .show(egui_ctx, |ui| {
+            let file = &mut settings.settings_file;
+            ui.heading("graphics");
+            ui.horizontal(|ui| {
+                pick_flag(ui, "backend", &mut file.backend, &BACKENDS);
+                let wanted =
+                    from_settings_file("backend", file.backend.as_deref(), extract_backend);
+                restart_required(ui, wanted != settings.desired_backend);
+            });
+            ui.horizontal(|ui| {
+                let mut device = file.device.clone().unwrap_or_default();
+                ui.label("device");
+                if ui.text_edit_singleline(&mut device).changed() {
+                    file.device = (!device.is_empty()).then_some(device);
+                }
+                let wanted = file.device.as_ref().map(|d| d.to_lowercase());
+                restart_required(ui, wanted != settings.desired_device_name);
+            });
+            ui.horizontal(|ui| {
+                pick_flag(ui, "profile", &mut file.profile, &PROFILES);
+                let wanted =
+                    from_settings_file("profile", file.profile.as_deref(), extract_profile);
+                restart_required(ui, wanted != settings.desired_profile);
+            });
+            ui.horizontal(|ui| {
+                let mut flag = VSYNCS
+                    .iter()
+                    .map(|(flag, _)| *flag)
+                    .find(|flag| extract_vsync(flag) == Ok(settings.present_mode))
+                    .unwrap_or(VSYNCS[0].0);
+                if pick(ui, "vsync", &mut flag, &VSYNCS) {
+                    file.vsync = Some(flag.to_owned());
+                    // the surface gets reconfigured before the next frame
+                    settings.present_mode = extract_vsync(flag).unwrap();
+                }
+            });
+            ui.horizontal(|ui| {
+                let running = if settings.samples == SampleCount::Four {
+                    4
+                } else {
+                    1
+                };
+                let mut msaa = file.msaa.unwrap_or(running);
+                if pick(ui, "msaa", &mut msaa, &MSAAS) {
+                    file.msaa = Some(msaa);
+                }
+                restart_required(ui, msaa != running);
+            });
+
+            ui.heading("audio");
+            for (label, group, volume, saved) in [
+                (
+                    "music",
+                    SoundGroup::BGM,
+                    &mut settings.bgm_volume,
+                    &mut file.bgm_volume,
+                ),
+                (
+                    "effects",
+                    SoundGroup::SFX,
+                    &mut settings.sfx_volume,
+                    &mut file.sfx_volume,
+                ),
+            ] {
+                if ui
+                    .add(egui::Slider::new(volume, 0.0..=1.0).text(label))
+                    .changed()
+                {
+                    *saved = Some(*volume);
+                    if let Some(orchestra) = orchestra {
+                        orchestra.send_cmd(AudioCommand::SetVolume(group, *volume));
+                    }
+                }
+            }
+
+            ui.heading("controls");
+            for (label, value, saved, range) in [
+                (
+                    "mouse sensitivity",
+                    &mut settings.mouse_sensitivity,
+                    &mut file.mouse_sensitivity,
+                    0.1..=5.0,
+                ),
+                (
+                    "stick deadzone",
+                    &mut settings.deadzone,
+                    &mut file.deadzone,
+                    0.0..=0.95,
+                ),
+                (
+                    "walk speed",
+                    &mut settings.walk_speed,
+                    &mut file.walk_speed,
+                    1.0..=100.0,
+                ),
+                (
+                    "run speed",
+                    &mut settings.run_speed,
+                    &mut file.run_speed,
+                    1.0..=200.0,
+                ),
+            ] {
+                if ui
+                    .add(egui::Slider::new(value, range).text(label))
+                    .changed()
+                {
+                    *saved = Some(*value);
+                }
+            }
+
+            ui.heading("bindings");
+            egui::ScrollArea::vertical()
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    keybindings_ui(ui, &mut state.keybindings, &mut state.rebinding);
+                });
+
+            ui.separator();
+            save = ui.button("save").clicked();
+        });
+    if !state.settings_panel_open {
+        state.rebinding = None;
+    }
+    if save {
+        state
+            .keybindings
+            .save_to(&mut settings.settings_file.keybindings);
+        settings.settings_file.save();
+    }
+}
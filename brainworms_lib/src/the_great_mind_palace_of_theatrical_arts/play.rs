@@ -1,4 +1,4 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, path::Path, sync::Arc};
 
 use brainworms_arson::egui;
 use egui::Context;
@@ -8,7 +8,7 @@ use parking_lot::Mutex;
 use rend3::Renderer;
 use tokio::runtime::Runtime;
 use uuid::Uuid;
-use winit::event_loop::EventLoop;
+use winit::event_loop::EventLoopProxy;
 use winit::window::Window;
 
 use crate::{theater::basement::input_handling::InputContext, GameProgrammeState, MyEvent};
@@ -32,6 +32,8 @@ pub struct Play<PlayablesEnum> {
     pub first_playable: Uuid,
     pub playables: HashMap<Uuid, PlayablesEnum>,
     pub playable_names: HashMap<String, Uuid>,
+    /// shown instead of a scene's own chorus while its stage is still loading
+    pub loading_curtain: Option<Uuid>,
 }
 
 #[enum_dispatch]
@@ -44,7 +46,7 @@ pub trait Playable<InputContextEnum: InputContext, UserData> {
     fn implement_playable(
         &mut self,
         settings: &GameProgrammeSettings,
-        event_loop: &EventLoop<MyEvent>,
+        event_loop_proxy: &EventLoopProxy<MyEvent>,
         renderer: Arc<Renderer>,
         routines: Arc<DefaultRoutines>,
         rts: &Runtime,
@@ -57,6 +59,7 @@ pub trait Playable<InputContextEnum: InputContext, UserData> {
         egui_ctx: Context,
         orchestra: Arc<Orchestra>,
         settings: &GameProgrammeSettings,
+        state: &mut GameProgrammeState<InputContextEnum>,
         user_data: Arc<Mutex<UserData>>,
     );
 
@@ -64,8 +67,48 @@ pub trait Playable<InputContextEnum: InputContext, UserData> {
         &mut self,
         settings: &GameProgrammeSettings,
         state: &mut GameProgrammeState<InputContextEnum>,
-        window: &Arc<Window>,
+        window: Option<&Arc<Window>>,
     );
+    fn playable_on_enter(&mut self, state: &mut GameProgrammeState<InputContextEnum>);
+    fn playable_on_exit(&mut self, state: &mut GameProgrammeState<InputContextEnum>);
+    fn playable_on_camera_arrived(
+        &mut self,
+        state: &mut GameProgrammeState<InputContextEnum>,
+        camera: &str,
+    );
+    fn playable_on_interaction(
+        &mut self,
+        state: &mut GameProgrammeState<InputContextEnum>,
+        callback: &str,
+        collider: &str,
+    );
+    fn playable_reads_file(&self, path: &Path) -> bool;
+}
+/// Hooks fired by the stagehands when a playable comes on or goes off stage.
+/// `on_exit` runs before the implementation is struck, so it can still look at it.
+pub trait Cued<InputContextEnum: InputContext> {
+    fn on_enter(&mut self, _state: &mut GameProgrammeState<InputContextEnum>) {}
+    fn on_exit(&mut self, _state: &mut GameProgrammeState<InputContextEnum>) {}
+    /// A move started with `move_camera_to` got to `camera`.
+    fn on_camera_arrived(
+        &mut self,
+        _state: &mut GameProgrammeState<InputContextEnum>,
+        _camera: &str,
+    ) {
+    }
+    /// `collider` was picked and the scene's `interactions` say to call `callback`.
+    fn on_interaction(
+        &mut self,
+        _state: &mut GameProgrammeState<InputContextEnum>,
+        _callback: &str,
+        _collider: &str,
+    ) {
+    }
+    /// Whether the playable is defined from what's in `path`, for hot reloading to know whose
+    /// file changed.
+    fn reads_file(&self, _path: &Path) -> bool {
+        false
+    }
 }
 #[derive(Debug, VariantFrom, Default)]
 pub enum Definitions {
@@ -86,7 +129,10 @@ pub enum Implementations {
 
 impl<
         InputContextEnum: InputContext,
-        T: Scenic<UserData> + Choral<UserData> + HandlesInputContexts<InputContextEnum>,
+        T: Scenic<UserData>
+            + Choral<InputContextEnum, UserData>
+            + HandlesInputContexts<InputContextEnum>
+            + Cued<InputContextEnum>,
         UserData: Default,
     > Playable<InputContextEnum, UserData> for T
 {
@@ -105,7 +151,7 @@ impl<
     fn implement_playable(
         &mut self,
         settings: &GameProgrammeSettings,
-        event_loop: &EventLoop<MyEvent>,
+        event_loop_proxy: &EventLoopProxy<MyEvent>,
         renderer: Arc<Renderer>,
         routines: Arc<DefaultRoutines>,
         rts: &Runtime,
@@ -113,7 +159,13 @@ impl<
         user_data: Arc<Mutex<UserData>>,
     ) {
         self.implement_scene(
-            settings, event_loop, renderer, routines, rts, orchestra, user_data,
+            settings,
+            event_loop_proxy,
+            renderer,
+            routines,
+            rts,
+            orchestra,
+            user_data,
         )
     }
 
@@ -125,9 +177,10 @@ impl<
         egui_ctx: Context,
         orchestra: Arc<Orchestra>,
         settings: &GameProgrammeSettings,
+        state: &mut GameProgrammeState<InputContextEnum>,
         user_data: Arc<Mutex<UserData>>,
     ) {
-        self.implement_chorus_for_choral(egui_ctx, orchestra, settings, user_data);
+        self.implement_chorus_for_choral(egui_ctx, orchestra, settings, state, user_data);
     }
 
     fn playable_definition(&mut self) -> &mut Definitions {
@@ -142,8 +195,41 @@ impl<
         &mut self,
         settings: &GameProgrammeSettings,
         state: &mut GameProgrammeState<InputContextEnum>,
-        window: &Arc<Window>,
+        window: Option<&Arc<Window>>,
     ) {
         self.handle_input_for_context(settings, state, window)
     }
+
+    fn playable_on_enter(&mut self, state: &mut GameProgrammeState<InputContextEnum>) {
+        self.on_enter(state)
+    }
+
+    fn playable_on_exit(&mut self, state: &mut GameProgrammeState<InputContextEnum>) {
+        self.on_exit(state)
+    }
+
+    fn playable_on_camera_arrived(
+        &mut self,
+        state: &mut GameProgrammeState<InputContextEnum>,
+        camera: &str,
+    ) {
+        self.on_camera_arrived(state, camera)
+    }
+
+    fn playable_on_interaction(
+        &mut self,
+        state: &mut GameProgrammeState<InputContextEnum>,
+        callback: &str,
+        collider: &str,
+    ) {
+        self.on_interaction(state, callback, collider)
+    }
+    fn playable_reads_file(&self, path: &Path) -> bool {
+        self.reads_file(path)
+    }
+}
+impl<PlayablesEnum> Play<PlayablesEnum> {
+    pub fn playable_id(&self, name: &str) -> Option<Uuid> {
+        self.playable_names.get(name).copied()
+    }
 }
@@ -1,2 +1,4 @@
 pub mod plumbing;
+pub mod prompt_book;
 pub mod pyrotechnics;
+pub mod stagehands;
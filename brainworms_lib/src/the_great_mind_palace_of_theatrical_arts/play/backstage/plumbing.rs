@@ -6,6 +6,7 @@ use glam::UVec2;
 use parking_lot::Mutex;
 use rend3::{types::SampleCount, Renderer, ShaderPreProcessor};
 use rend3_routine::base::BaseRenderGraph;
+use serde::{de::DeserializeOwned, Serialize};
 use wgpu::{Features, Instance, PresentMode, Surface};
 use winit::{
     error::EventLoopError,
@@ -16,20 +17,20 @@ use winit::{
 use crate::{
     theater::{
         basement::{cla::GameProgrammeSettings, input_handling::InputContext},
-        play::{
-            scene::{actors::AstinkSprite, AstinkScene},
-            Play, Playable,
-        },
+        play::{backstage::pyrotechnics::transitions::TransitionRoutine, Play, Playable},
     },
-    Event, GameProgramme, GameProgrammeData, GameProgrammeState, MyWinitEvent,
+    Event, GameProgramme, GameProgrammeData, GameProgrammeState, MyEvent,
 };
 
 pub mod asset_loader;
+pub mod hot_reload;
 
 pub struct DefaultRoutines {
     pub pbr: Mutex<rend3_routine::pbr::PbrRoutine>,
     pub skybox: Mutex<rend3_routine::skybox::SkyboxRoutine>,
     pub tonemapping: Mutex<rend3_routine::tonemapping::TonemappingRoutine>,
+    /// None when the transition shader couldn't be loaded, in which case every scene change is a cut
+    pub transition: Mutex<Option<TransitionRoutine>>,
 }
 pub(crate) struct StoredSurfaceInfo {
     pub(crate) size: UVec2,
@@ -41,7 +42,7 @@ pub(crate) struct StoredSurfaceInfo {
 impl<
         PlayablesEnum: Playable<InputContextEnum, UserData> + 'static,
         InputContextEnum: InputContext,
-        UserData: Default + 'static,
+        UserData: Default + Serialize + DeserializeOwned + 'static,
     > GameProgramme<PlayablesEnum, InputContextEnum, UserData>
 {
     pub fn start(self, window_builder: WindowBuilder) {
@@ -70,6 +71,7 @@ impl<
             rts: tokio::runtime::Builder::new_multi_thread().build().ok(),
             state: GameProgrammeState::default(),
             user_data: Arc::new(Mutex::new(UserData::default())),
+            save_migration: None,
         }
     }
 
@@ -147,18 +149,10 @@ impl<
                     surface_info.present_mode,
                 );
 
-                let alpha_mode = wgpu::CompositeAlphaMode::Auto;
-
-                let config = wgpu::SurfaceConfiguration {
-                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_DST,
-                    format: wgpu::TextureFormat::Bgra8Unorm,
-                    width: window.inner_size().width,
-                    height: window.inner_size().height,
-                    present_mode: wgpu::PresentMode::Immediate,
-                    alpha_mode,
-                    view_formats: Vec::new(),
-                    desired_maximum_frame_latency: 2,
-                };
+                let config = surface_configuration(
+                    UVec2::new(window.inner_size().width, window.inner_size().height),
+                    surface_info.present_mode,
+                );
 
                 surface
                     .as_ref()
@@ -169,6 +163,24 @@ impl<
                 renderer.set_aspect_ratio(size.x as f32 / size.y as f32);
                 Some(false)
             }
+            // vsync got changed in the settings panel
+            Event::AboutToWait if surface_info.present_mode != self.present_mode() => {
+                surface_info.present_mode = self.present_mode();
+                if let Some(surface) = surface.as_ref() {
+                    rend3::configure_surface(
+                        surface,
+                        &renderer.device,
+                        format,
+                        surface_info.size,
+                        surface_info.present_mode,
+                    );
+                    surface.configure(
+                        &renderer.device,
+                        &surface_configuration(surface_info.size, surface_info.present_mode),
+                    );
+                }
+                None
+            }
             _ => None,
         }
     }
@@ -190,7 +202,7 @@ impl<
     pub(crate) fn create_window(
         &mut self,
         builder: WindowBuilder,
-    ) -> Result<(EventLoop<MyWinitEvent<AstinkScene, AstinkSprite>>, Window), EventLoopError> {
+    ) -> Result<(EventLoop<MyEvent>, Window), EventLoopError> {
         profiling::scope!("creating window");
 
         let event_loop = EventLoopBuilder::with_user_event().build()?;
@@ -203,6 +215,26 @@ impl<
         self.settings.present_mode
     }
 }
+// COPY_SRC so the last frame of a playable can be captured for transitions
+pub(crate) fn surface_configuration(
+    size: UVec2,
+    present_mode: PresentMode,
+) -> wgpu::SurfaceConfiguration {
+    let alpha_mode = wgpu::CompositeAlphaMode::Auto;
+
+    wgpu::SurfaceConfiguration {
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+            | wgpu::TextureUsages::COPY_DST
+            | wgpu::TextureUsages::COPY_SRC,
+        format: wgpu::TextureFormat::Bgra8Unorm,
+        width: size.x,
+        height: size.y,
+        present_mode,
+        alpha_mode,
+        view_formats: Vec::new(),
+        desired_maximum_frame_latency: 2,
+    }
+}
 pub(crate) fn create_base_rendergraph(
     renderer: &Arc<Renderer>,
     spp: &ShaderPreProcessor,
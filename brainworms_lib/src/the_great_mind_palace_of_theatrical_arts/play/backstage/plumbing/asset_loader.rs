@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 
+use parking_lot::Mutex;
 use rend3::util::typedefs::SsoString;
 use thiserror::Error;
 
@@ -13,6 +14,34 @@ pub enum AssetError {
     },
 }
 
+// (finished, started) since the last time everything was done loading
+static LOADS: Mutex<(usize, usize)> = Mutex::new((0, 0));
+
+/// Held by whatever is loading something for a playable, so the curtain knows how far along
+/// it is. The load counts as done when the ticket is dropped, whether it worked or not.
+pub struct LoadTicket(());
+impl LoadTicket {
+    pub fn take() -> Self {
+        LOADS.lock().1 += 1;
+        Self(())
+    }
+}
+impl Drop for LoadTicket {
+    fn drop(&mut self) {
+        let mut loads = LOADS.lock();
+        loads.0 += 1;
+        if loads.0 >= loads.1 {
+            *loads = (0, 0);
+        }
+    }
+}
+
+/// How many of the pending loads are done out of how many there are, `None` when nothing is loading.
+pub fn load_progress() -> Option<(usize, usize)> {
+    let (finished, started) = *LOADS.lock();
+    (started > 0).then_some((finished, started))
+}
+
 pub enum AssetPath<'a> {
     Internal(&'a str),
     External(&'a str),
@@ -0,0 +1,384 @@
+// dev-mode file watching so tweaking a scene file or a shader doesn't mean restarting the programme
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use log::{info, warn};
+use notify::{event::ModifyKind, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use rend3_gltf::GltfLoadSettings;
+use serde::{de::DeserializeOwned, Serialize};
+use uuid::Uuid;
+use winit::event_loop::EventLoopProxy;
+
+use crate::{
+    theater::{
+        basement::input_handling::InputContext,
+        play::{
+            backstage::pyrotechnics::transitions::{
+                reload_transition_routine, TRANSITION_SHADER_PATH,
+            },
+            curtain::CurtainDefinition,
+            scene::{
+                actors::{create_actor, ActressDefinition},
+                definitions::load_definition,
+                props::create_prop,
+                stage3d::{cast_cameras, load_stage3d},
+                SceneDefinition, SceneImplementation,
+            },
+            Definitions, Implementations, Playable,
+        },
+    },
+    GameProgramme, MyEvent, MyWinitEvent,
+};
+
+// the transition shader is the only one read from disk, the rest are built into rend3
+pub const WATCHED_DIRECTORIES: [&str; 3] = ["assets", "lfs_scam", "shaders/transitions"];
+// editors like to fire several events for a single save
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HotReload {
+    SceneFile(PathBuf),
+    Markup(PathBuf),
+    /// [`TRANSITION_SHADER_PATH`], nothing else gets its pipeline rebuilt
+    TransitionShader,
+    Stage(PathBuf),
+}
+impl HotReload {
+    fn from_path(path: PathBuf) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "ron" | "toml" => Some(Self::SceneFile(path)),
+            "txt" => Some(Self::Markup(path)),
+            "wgsl" if path == Path::new(TRANSITION_SHADER_PATH) => Some(Self::TransitionShader),
+            "glb" | "gltf" => Some(Self::Stage(path)),
+            _ => None,
+        }
+    }
+}
+
+/// Watch [`WATCHED_DIRECTORIES`] and send a [`MyWinitEvent::HotReload`] for every file we know how
+/// to reload. Watching stops when the returned watcher is dropped.
+pub fn watch_for_changes(
+    event_loop_proxy: EventLoopProxy<MyEvent>,
+) -> notify::Result<RecommendedWatcher> {
+    let working_directory = std::env::current_dir().unwrap_or_default();
+    let mut last_sent: HashMap<PathBuf, Instant> = HashMap::new();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        let event = match res {
+            Ok(event) => event,
+            Err(e) => {
+                warn!("file watcher: {e}");
+                return;
+            }
+        };
+        if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+            || matches!(event.kind, EventKind::Modify(ModifyKind::Metadata(_)))
+        {
+            return;
+        }
+        for path in event.paths {
+            // asset paths are relative to the working directory
+            let path = path
+                .strip_prefix(&working_directory)
+                .map(Path::to_path_buf)
+                .unwrap_or(path);
+            if last_sent.get(&path).is_some_and(|t| t.elapsed() < DEBOUNCE) {
+                continue;
+            }
+            let Some(change) = HotReload::from_path(path.clone()) else {
+                continue;
+            };
+            last_sent.insert(path, Instant::now());
+            let _ = event_loop_proxy.send_event(MyWinitEvent::HotReload(change));
+        }
+    })?;
+    for directory in WATCHED_DIRECTORIES {
+        if let Err(e) = watcher.watch(Path::new(directory), RecursiveMode::Recursive) {
+            warn!("not watching {directory} for changes: {e}");
+        }
+    }
+    Ok(watcher)
+}
+
+impl<
+        PlayablesEnum: Playable<InputContextEnum, UserData> + 'static,
+        InputContextEnum: InputContext + 'static,
+        UserData: Default + Serialize + DeserializeOwned + 'static,
+    > GameProgramme<PlayablesEnum, InputContextEnum, UserData>
+{
+    pub(crate) fn hot_reload(&mut self, change: HotReload) {
+        info!("hot reloading {change:?}");
+        match change {
+            HotReload::TransitionShader => self.reload_transition_shader(),
+            HotReload::SceneFile(path) => {
+                for playable_id in self.readers_of(&path) {
+                    if self.scene_file_is_sound(playable_id, &path) {
+                        self.redefine_playable(playable_id);
+                    }
+                }
+            }
+            HotReload::Markup(path) => {
+                for playable_id in self.readers_of(&path) {
+                    self.redefine_playable(playable_id);
+                }
+            }
+            HotReload::Stage(path) => self.reload_stage(&path),
+        }
+    }
+
+    fn reload_transition_shader(&mut self) {
+        let (Some(renderer), Some(routines), Some(format)) = (
+            self.state.renderer.as_ref(),
+            self.state.routines.as_ref(),
+            self.state.surface_format,
+        ) else {
+            return;
+        };
+        reload_transition_routine(renderer, format, &routines.transition);
+    }
+
+    /// Every playable that's defined from `path`, on stage or not. The curtain is still the
+    /// loading screen while it's off.
+    fn readers_of(&self, path: &Path) -> Vec<Uuid> {
+        let readers: Vec<Uuid> = self
+            .data
+            .play
+            .playables
+            .iter()
+            .filter(|(_, playable)| playable.playable_reads_file(path))
+            .map(|(playable_id, _)| *playable_id)
+            .collect();
+        if readers.is_empty() {
+            info!("nothing is defined from {}", path.display());
+        }
+        readers
+    }
+
+    /// Parse the changed file as whatever the playable is defined by, so a typo gets logged
+    /// instead of taking the whole programme down when the playable redefines itself.
+    fn scene_file_is_sound(&mut self, playable_id: Uuid, path: &Path) -> bool {
+        let Some(playable) = self.data.play.playables.get_mut(&playable_id) else {
+            return false;
+        };
+        let path = path.to_string_lossy();
+        let checked = match playable.playable_definition() {
+            Definitions::SceneDefinition(_) => load_definition::<SceneDefinition>(&path).err(),
+            Definitions::CurtainDefinition(_) => load_definition::<CurtainDefinition>(&path).err(),
+            Definitions::BogusDefinition => None,
+        };
+        match checked {
+            Some(e) => {
+                warn!("not reloading: {e}");
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// Define the playable again and re-implement just the parts whose definition changed, a
+    /// curtain all over again. The camera stays wherever it is, and so does the old definition if
+    /// the new one won't load.
+    fn redefine_playable(&mut self, playable_id: Uuid) {
+        let Some(playable) = self.data.play.playables.get_mut(&playable_id) else {
+            return;
+        };
+        let old_definition = std::mem::take(playable.playable_definition());
+        playable.define_playable();
+        let new_definition = std::mem::take(playable.playable_definition());
+        if matches!(new_definition, Definitions::BogusDefinition) {
+            warn!(
+                "{} didn't redefine itself, keeping what it had",
+                playable.playable_name()
+            );
+            *playable.playable_definition() = old_definition;
+            return;
+        }
+        if let (
+            Definitions::SceneDefinition(old_definition),
+            Definitions::SceneDefinition(new_definition),
+            Some(Implementations::SceneImplementation(implementation)),
+            Some(renderer),
+            Some(event_loop_proxy),
+            Some(rts),
+        ) = (
+            &old_definition,
+            &new_definition,
+            playable.playable_implementation().as_mut(),
+            self.state.renderer.as_ref(),
+            self.state.event_loop_proxy.as_ref(),
+            self.rts.as_ref(),
+        ) {
+            restage_changes(
+                old_definition,
+                new_definition,
+                implementation,
+                playable_id,
+                rts,
+                renderer,
+                event_loop_proxy,
+                self.settings.gltf_settings,
+            );
+        }
+        let recurtain = match (&old_definition, &new_definition) {
+            (Definitions::CurtainDefinition(old), Definitions::CurtainDefinition(new)) => {
+                old != new
+            }
+            _ => false,
+        };
+        *playable.playable_definition() = new_definition;
+        let Some(egui_routine) = self.state.egui_routine.as_mut().filter(|_| recurtain) else {
+            return;
+        };
+        let Some(Implementations::CurtainImplementation(curtain)) =
+            playable.playable_implementation()
+        else {
+            return;
+        };
+        curtain.free_textures(egui_routine);
+        *playable.playable_implementation() = None;
+        info!("re-implementing {}", playable.playable_name());
+        self.rig_playable(playable_id);
+    }
+
+    fn reload_stage(&mut self, path: &Path) {
+        let Some(playable_id) = self.state.current_playable else {
+            return;
+        };
+        let Some(playable) = self.data.play.playables.get_mut(&playable_id) else {
+            return;
+        };
+        if !matches!(
+            playable.playable_implementation(),
+            Some(Implementations::SceneImplementation(_))
+        ) {
+            return;
+        }
+        let Definitions::SceneDefinition(definition) = playable.playable_definition() else {
+            return;
+        };
+        let (name, directory) = &definition.stage;
+        if Path::new(&format!("{directory}/{name}.glb")) != path {
+            info!("{} isn't the current stage", path.display());
+            return;
+        }
+        let (Some(renderer), Some(event_loop_proxy), Some(rts)) = (
+            self.state.renderer.as_ref(),
+            self.state.event_loop_proxy.as_ref(),
+            self.rts.as_ref(),
+        ) else {
+            return;
+        };
+        rts.spawn(load_stage3d(
+            name.clone(),
+            directory.clone(),
+            playable_id,
+            Arc::clone(renderer),
+            self.settings.gltf_settings,
+            event_loop_proxy.clone(),
+            definition.collider_ids(),
+        ));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn restage_changes(
+    old_definition: &SceneDefinition,
+    new_definition: &SceneDefinition,
+    implementation: &mut SceneImplementation,
+    playable_id: Uuid,
+    rts: &tokio::runtime::Runtime,
+    renderer: &Arc<rend3::Renderer>,
+    event_loop_proxy: &EventLoopProxy<MyEvent>,
+    gltf_settings: GltfLoadSettings,
+) {
+    if old_definition == new_definition {
+        return;
+    }
+    if old_definition.cameras != new_definition.cameras {
+        info!("re-implementing cameras");
+        implementation.cameras = cast_cameras(new_definition, &implementation.stage3d);
+    }
+
+    if old_definition.stage != new_definition.stage
+        || old_definition.collider_ids() != new_definition.collider_ids()
+    {
+        // the old stage stays up until the new one arrives
+        info!("re-implementing the stage");
+        rts.spawn(load_stage3d(
+            new_definition.stage.0.clone(),
+            new_definition.stage.1.clone(),
+            playable_id,
+            Arc::clone(renderer),
+            gltf_settings,
+            event_loop_proxy.clone(),
+            new_definition.collider_ids(),
+        ));
+    }
+
+    implementation.actresses.retain(|name, _| {
+        let still_cast = new_definition.actors.iter().any(|a| &a.name == name);
+        if !still_cast {
+            info!("{name} has been written out");
+        }
+        still_cast
+    });
+    for actress in &new_definition.actors {
+        if old_definition.actors.contains(actress) {
+            continue;
+        }
+        info!("re-implementing {}", actress.name);
+        spawn_actress(
+            rts,
+            actress.clone(),
+            Arc::clone(renderer),
+            event_loop_proxy.clone(),
+            playable_id,
+        );
+    }
+
+    implementation
+        .props
+        .retain(|name, _| new_definition.props.iter().any(|p| &p.name == name));
+    for prop in &new_definition.props {
+        if old_definition.props.contains(prop) {
+            continue;
+        }
+        info!("re-implementing {}", prop.name);
+        rts.spawn(create_prop(
+            prop.clone(),
+            Arc::clone(renderer),
+            gltf_settings,
+            event_loop_proxy.clone(),
+            playable_id,
+        ));
+    }
+}
+
+fn spawn_actress(
+    rts: &tokio::runtime::Runtime,
+    ActressDefinition {
+        name,
+        directory,
+        transform,
+        size,
+    }: ActressDefinition,
+    renderer: Arc<rend3::Renderer>,
+    event_loop_proxy: EventLoopProxy<MyEvent>,
+    sc_id: Uuid,
+) {
+    rts.spawn(async move {
+        create_actor(
+            name,
+            directory,
+            renderer,
+            event_loop_proxy,
+            transform,
+            size,
+            sc_id,
+        )
+        .await
+    });
+}
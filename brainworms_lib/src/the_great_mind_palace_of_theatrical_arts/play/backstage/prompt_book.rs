@@ -0,0 +1,254 @@
+// the prompt book: where the show was at, written down so it can pick up from there next time
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use glam::Mat3A;
+use log::{info, warn};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    theater::{
+        basement::input_handling::InputContext,
+        play::{
+            orchestra::AudioState,
+            scene::{CamInfo, Camera},
+            Playable,
+        },
+    },
+    GameProgramme,
+};
+
+/// Bump this whenever [`SaveGame`] changes shape, and teach the game's [`SaveMigration`] about it.
+pub const SAVE_VERSION: u32 = 1;
+pub const SAVE_DIRECTORY: &str = "saves";
+
+/// Brings a save written by an older version up to date. Gets the version the file was written
+/// with and its contents, returns it the way [`SAVE_VERSION`] would have written it.
+pub type SaveMigration = fn(from_version: u32, save: String) -> Result<String, String>;
+
+#[derive(Debug, Error)]
+pub enum SaveError {
+    #[error("can't get at {}: {error}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        error: io::Error,
+    },
+    #[error("{}: {message}", path.display())]
+    Malformed { path: PathBuf, message: String },
+    #[error("{} is from version {version}, newer than this programme", path.display())]
+    TooNew { path: PathBuf, version: u32 },
+    #[error("{} is from version {version} and there's no migration for it", path.display())]
+    NoMigration { path: PathBuf, version: u32 },
+    #[error("{}: migrating from version {version} failed: {message}", path.display())]
+    MigrationFailed {
+        path: PathBuf,
+        version: u32,
+        message: String,
+    },
+    #[error("nothing on stage worth saving")]
+    NothingOnStage,
+    #[error("the save is for {0}, which isn't in this play")]
+    UnknownPlayable(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveGame<InputContextEnum, UserData> {
+    pub version: u32,
+    /// seconds since the unix epoch
+    pub saved_at: u64,
+    pub playable: String,
+    pub camera: Option<(String, CamInfo)>,
+    pub input_context: InputContextEnum,
+    pub user_data: UserData,
+    #[serde(default)]
+    pub audio: AudioState,
+}
+
+// just enough of a save to know whether it needs migrating
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+pub fn slot_path(slot: &str) -> PathBuf {
+    Path::new(SAVE_DIRECTORY).join(format!("{slot}.ron"))
+}
+
+/// Every slot in [`SAVE_DIRECTORY`], most recently written first.
+pub fn save_slots() -> Vec<String> {
+    let Ok(entries) = fs::read_dir(SAVE_DIRECTORY) else {
+        return vec![];
+    };
+    let mut slots: Vec<(SystemTime, String)> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "ron" {
+                return None;
+            }
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some((modified, path.file_stem()?.to_string_lossy().into_owned()))
+        })
+        .collect();
+    slots.sort_by(|a, b| b.0.cmp(&a.0));
+    slots.into_iter().map(|(_, slot)| slot).collect()
+}
+
+pub fn latest_save_slot() -> Option<String> {
+    save_slots().into_iter().next()
+}
+
+fn read_save<InputContextEnum: DeserializeOwned, UserData: DeserializeOwned>(
+    slot: &str,
+    migration: Option<SaveMigration>,
+) -> Result<SaveGame<InputContextEnum, UserData>, SaveError> {
+    let path = slot_path(slot);
+    let mut text = fs::read_to_string(&path).map_err(|error| SaveError::Io {
+        path: path.clone(),
+        error,
+    })?;
+    let malformed = |e: ron::error::SpannedError| SaveError::Malformed {
+        path: path.clone(),
+        message: e.to_string(),
+    };
+    let SaveHeader { version } = ron::from_str(&text).map_err(malformed)?;
+    if version > SAVE_VERSION {
+        return Err(SaveError::TooNew {
+            path: path.clone(),
+            version,
+        });
+    }
+    if version < SAVE_VERSION {
+        let Some(migration) = migration else {
+            return Err(SaveError::NoMigration {
+                path: path.clone(),
+                version,
+            });
+        };
+        info!("migrating {} from version {version}", path.display());
+        text = migration(version, text).map_err(|message| SaveError::MigrationFailed {
+            path: path.clone(),
+            version,
+            message,
+        })?;
+    }
+    ron::from_str(&text).map_err(malformed)
+}
+
+fn write_save<T: Serialize>(slot: &str, save: &T) -> Result<(), SaveError> {
+    let path = slot_path(slot);
+    let io_error = |error| SaveError::Io {
+        path: path.clone(),
+        error,
+    };
+    let text =
+        ron::ser::to_string_pretty(save, ron::ser::PrettyConfig::default()).map_err(|e| {
+            SaveError::Malformed {
+                path: path.clone(),
+                message: e.to_string(),
+            }
+        })?;
+    fs::create_dir_all(SAVE_DIRECTORY).map_err(io_error)?;
+    // a crash halfway through writing shouldn't eat the old save
+    let unfinished = path.with_extension("ron.unfinished");
+    fs::write(&unfinished, text).map_err(io_error)?;
+    fs::rename(&unfinished, &path).map_err(io_error)
+}
+
+impl<
+        PlayablesEnum: Playable<InputContextEnum, UserData> + 'static,
+        InputContextEnum: InputContext + 'static,
+        UserData: Default + Serialize + DeserializeOwned + 'static,
+    > GameProgramme<PlayablesEnum, InputContextEnum, UserData>
+{
+    /// Write down the current playable, camera, input context, user data and audio to `slot`.
+    /// The loading curtain is never saved, whatever it's covering is.
+    pub fn save_to_slot(&self, slot: &str) -> Result<(), SaveError> {
+        let (playable_id, camera) = match self.state.current_playable {
+            Some(current) if self.data.play.loading_curtain != Some(current) => {
+                (current, self.state.cur_camera.clone())
+            }
+            _ => self
+                .state
+                .playable_stack
+                .last()
+                .cloned()
+                .ok_or(SaveError::NothingOnStage)?,
+        };
+        let playable = self
+            .data
+            .play
+            .playables
+            .get(&playable_id)
+            .ok_or(SaveError::NothingOnStage)?;
+        let user_data = self.user_data.lock();
+        let save = SaveGame {
+            version: SAVE_VERSION,
+            saved_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            playable: playable.playable_name().to_owned(),
+            camera: camera.map(|c| (c.name, c.info)),
+            input_context: self.state.input_contexts.base(),
+            user_data: &*user_data,
+            audio: self
+                .state
+                .orchestra
+                .as_ref()
+                .map(|o| o.audio_state())
+                .unwrap_or_default(),
+        };
+        write_save(slot, &save)?;
+        info!("saved to {slot}");
+        Ok(())
+    }
+
+    /// Strike everything on stage and pick up where `slot` left off.
+    pub fn load_from_slot(&mut self, slot: &str) -> Result<(), SaveError> {
+        let save: SaveGame<InputContextEnum, UserData> = read_save(slot, self.save_migration)?;
+        let next = self
+            .data
+            .play
+            .playable_id(&save.playable)
+            .ok_or_else(|| SaveError::UnknownPlayable(save.playable.clone()))?;
+        let covered: Vec<_> = self
+            .state
+            .playable_stack
+            .drain(..)
+            .map(|(id, _)| id)
+            .collect();
+        if let Some(current) = self.state.current_playable {
+            self.strike_playable(current);
+        }
+        for playable_id in covered {
+            self.strike_playable(playable_id);
+        }
+        // nothing from the game being left carries on into the loaded one
+        if let Some(orchestra) = &self.state.orchestra {
+            orchestra.stop_all();
+        }
+        // the playable might look at these while implementing itself
+        *self.user_data.lock() = save.user_data;
+        self.state.reset_input_contexts(save.input_context);
+        self.stage_playable(next);
+        if let Some((name, info)) = save.camera {
+            self.state.cur_camera = Some(Camera {
+                name,
+                renderer_camera: rend3::types::Camera::default(),
+                info,
+                rotation: Mat3A::default(),
+            });
+        }
+        match &self.state.orchestra {
+            Some(orchestra) => orchestra.restore_audio_state(&save.audio, next),
+            None => warn!("no orchestra to restore the audio with"),
+        }
+        info!("loaded {slot}");
+        Ok(())
+    }
+}
@@ -1 +1,2 @@
 pub mod kinetic_narrative;
+pub mod transitions;
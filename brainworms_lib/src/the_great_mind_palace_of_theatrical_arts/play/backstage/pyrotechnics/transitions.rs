@@ -0,0 +1,303 @@
+// blending the outgoing playable into the incoming one so scene changes don't hard-cut
+use std::time::Duration;
+
+use bytemuck::{Pod, Zeroable};
+use log::warn;
+use parking_lot::Mutex;
+use rend3::Renderer;
+
+use crate::theater::{
+    basement::easing::Easing,
+    play::backstage::plumbing::asset_loader::{AssetLoader, AssetPath},
+};
+
+pub const TRANSITION_SHADER_PATH: &str = "shaders/transitions/transitions.wgsl";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionKind {
+    #[default]
+    Cut,
+    FadeToBlack,
+    Crossfade,
+    Wipe,
+    /// blocky radiation-green dissolve with scanline glitches
+    TheracDissolve,
+}
+impl TransitionKind {
+    fn shader_id(&self) -> u32 {
+        match self {
+            TransitionKind::Cut => 0,
+            TransitionKind::FadeToBlack => 1,
+            TransitionKind::Crossfade => 2,
+            TransitionKind::Wipe => 3,
+            TransitionKind::TheracDissolve => 4,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TransitionEffect {
+    pub kind: TransitionKind,
+    pub duration: Duration,
+    pub easing: Easing,
+}
+impl TransitionEffect {
+    pub fn new(kind: TransitionKind, duration: Duration, easing: Easing) -> Self {
+        Self {
+            kind,
+            duration,
+            easing,
+        }
+    }
+    pub fn is_cut(&self) -> bool {
+        self.kind == TransitionKind::Cut || self.duration.is_zero()
+    }
+    /// Eased progress of the effect after `elapsed`, and whether it's over.
+    pub fn progress(&self, elapsed: Duration) -> (f32, bool) {
+        let linear = (elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0);
+        (self.easing.apply(linear), linear >= 1.0)
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct TransitionUniforms {
+    progress: f32,
+    kind: u32,
+    time: f32,
+    _padding: f32,
+}
+
+/// Draws the captured last frame of the outgoing playable over the freshly rendered incoming one.
+/// Every effect is expressed as an overlay with per-pixel alpha so the base rendergraph stays untouched.
+pub struct TransitionRoutine {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    uniforms: wgpu::Buffer,
+    outgoing: Option<(wgpu::Texture, wgpu::BindGroup)>,
+}
+
+impl TransitionRoutine {
+    pub fn new(renderer: &Renderer, format: wgpu::TextureFormat, shader_source: &str) -> Self {
+        let device = &renderer.device;
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("transitions"),
+            source: wgpu::ShaderSource::Wgsl(shader_source.into()),
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("transition bgl"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("transition pipeline layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("transition pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("transition sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let uniforms = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("transition uniforms"),
+            size: std::mem::size_of::<TransitionUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        Self {
+            pipeline,
+            bind_group_layout,
+            sampler,
+            uniforms,
+            outgoing: None,
+        }
+    }
+
+    /// Copy the frame that is about to be presented so it can be blended away later.
+    /// The surface has to be configured with `COPY_SRC` for this.
+    pub fn capture(&mut self, renderer: &Renderer, frame: &wgpu::Texture) {
+        let size = frame.size();
+        let outgoing = renderer.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("outgoing playable"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: frame.format(),
+            usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let mut encoder = renderer
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("transition capture encoder"),
+            });
+        encoder.copy_texture_to_texture(frame.as_image_copy(), outgoing.as_image_copy(), size);
+        renderer.queue.submit(std::iter::once(encoder.finish()));
+
+        let view = outgoing.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = renderer
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("transition bind group"),
+                layout: &self.bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: self.uniforms.as_entire_binding(),
+                    },
+                ],
+            });
+        self.outgoing = Some((outgoing, bind_group));
+    }
+
+    pub fn draw(
+        &self,
+        renderer: &Renderer,
+        frame: &wgpu::Texture,
+        kind: TransitionKind,
+        progress: f32,
+        time: f32,
+    ) {
+        let Some((_, bind_group)) = &self.outgoing else {
+            return;
+        };
+        renderer.queue.write_buffer(
+            &self.uniforms,
+            0,
+            bytemuck::bytes_of(&TransitionUniforms {
+                progress,
+                kind: kind.shader_id(),
+                time,
+                _padding: 0.0,
+            }),
+        );
+        let view = frame.create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = renderer
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("transition overlay encoder"),
+            });
+        {
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("transition overlay"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            rpass.set_pipeline(&self.pipeline);
+            rpass.set_bind_group(0, bind_group, &[]);
+            rpass.draw(0..3, 0..1);
+        }
+        renderer.queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    /// Drop the captured frame once the transition is over.
+    pub fn finish(&mut self) {
+        self.outgoing = None;
+    }
+}
+
+pub(crate) fn load_transition_routine(
+    renderer: &Renderer,
+    format: wgpu::TextureFormat,
+) -> Option<TransitionRoutine> {
+    let loader = AssetLoader::default();
+    match pollster::block_on(loader.get_asset(AssetPath::Internal(TRANSITION_SHADER_PATH))) {
+        Ok(source) => Some(TransitionRoutine::new(
+            renderer,
+            format,
+            &String::from_utf8_lossy(&source),
+        )),
+        Err(e) => {
+            warn!("scene transitions will all be cuts: {e}");
+            None
+        }
+    }
+}
+
+/// Rebuild the routine from the shader on disk, keeping the old one if the new source doesn't validate.
+pub(crate) fn reload_transition_routine(
+    renderer: &Renderer,
+    format: wgpu::TextureFormat,
+    routine: &Mutex<Option<TransitionRoutine>>,
+) {
+    renderer
+        .device
+        .push_error_scope(wgpu::ErrorFilter::Validation);
+    let reloaded = load_transition_routine(renderer, format);
+    if let Some(e) = pollster::block_on(renderer.device.pop_error_scope()) {
+        warn!("keeping the old transition shader: {e}");
+        return;
+    }
+    if reloaded.is_some() {
+        *routine.lock() = reloaded;
+    }
+}
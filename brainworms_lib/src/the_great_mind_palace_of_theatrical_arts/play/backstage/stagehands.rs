@@ -0,0 +1,351 @@
+// the people who actually move the scenery around between scenes
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use log::{info, warn};
+use serde::{de::DeserializeOwned, Serialize};
+use uuid::Uuid;
+use winit::event_loop::EventLoopWindowTarget;
+
+use crate::{
+    theater::{
+        basement::input_handling::InputContext,
+        play::{
+            backstage::pyrotechnics::transitions::TransitionEffect,
+            scene::{
+                stage3d::{cast_cameras, Colliders},
+                AstinkScene, LoadedStage3D, SceneImplementation,
+            },
+            Definitions, Implementations, Playable,
+        },
+    },
+    GameProgramme, GameProgrammeState, MyEvent,
+};
+
+/// Requests to change the playable on stage. They are queued on [`GameProgrammeState`] and
+/// carried out after the current playable is done handling input for the frame.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StageDirection {
+    /// Put the named playable on top, keeping the current one implemented underneath.
+    Push(String),
+    /// Strike the current playable and go back to the one it was covering.
+    Pop,
+    /// Strike the current playable and put the named one in its place.
+    Replace(String),
+    /// Everybody off, the programme is over.
+    Exeunt,
+    /// Write where the show is at to the named save slot.
+    SaveTo(String),
+    /// Strike everything and pick up from the named save slot.
+    LoadFrom(String),
+}
+
+/// Where a scene change with a visual effect is at. The render loop captures the outgoing frame,
+/// the stagehands swap the playables on the next `AboutToWait`, then the render loop blends.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransitionPhase {
+    Capture,
+    Switch,
+    Blend(Instant),
+}
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneChange {
+    pub direction: StageDirection,
+    pub effect: TransitionEffect,
+    pub phase: TransitionPhase,
+}
+
+impl<InputContextEnum: InputContext> GameProgrammeState<InputContextEnum> {
+    pub fn direct(&mut self, direction: StageDirection) {
+        self.direct_with_effect(direction, TransitionEffect::default());
+    }
+    pub fn direct_with_effect(&mut self, direction: StageDirection, effect: TransitionEffect) {
+        self.stage_directions.push_back((direction, effect));
+    }
+    pub fn push_playable(&mut self, name: &str) {
+        self.direct(StageDirection::Push(name.to_owned()));
+    }
+    pub fn pop_playable(&mut self) {
+        self.direct(StageDirection::Pop);
+    }
+    pub fn replace_playable(&mut self, name: &str) {
+        self.direct(StageDirection::Replace(name.to_owned()));
+    }
+    pub fn exeunt(&mut self) {
+        self.direct(StageDirection::Exeunt);
+    }
+    pub fn save_game(&mut self, slot: &str) {
+        self.direct(StageDirection::SaveTo(slot.to_owned()));
+    }
+    pub fn load_game(&mut self, slot: &str) {
+        self.direct(StageDirection::LoadFrom(slot.to_owned()));
+    }
+}
+
+impl<
+        PlayablesEnum: Playable<InputContextEnum, UserData> + 'static,
+        InputContextEnum: InputContext + 'static,
+        UserData: Default + Serialize + DeserializeOwned + 'static,
+    > GameProgramme<PlayablesEnum, InputContextEnum, UserData>
+{
+    pub(crate) fn follow_stage_directions(
+        &mut self,
+        event_loop_window_target: &EventLoopWindowTarget<MyEvent>,
+    ) {
+        if let Some(scene_change) = self.state.scene_change.as_mut() {
+            if scene_change.phase == TransitionPhase::Switch {
+                scene_change.phase = TransitionPhase::Blend(Instant::now());
+                let direction = scene_change.direction.clone();
+                self.carry_out(direction, event_loop_window_target);
+            }
+            // one scene change at a time while an effect is running
+            return;
+        }
+        while let Some((direction, effect)) = self.state.stage_directions.pop_front() {
+            let can_transition = self
+                .state
+                .routines
+                .as_ref()
+                .is_some_and(|r| r.transition.lock().is_some());
+            if effect.is_cut() || !can_transition {
+                self.carry_out(direction, event_loop_window_target);
+            } else {
+                self.state.scene_change = Some(SceneChange {
+                    direction,
+                    effect,
+                    phase: TransitionPhase::Capture,
+                });
+                return;
+            }
+        }
+    }
+
+    fn carry_out(
+        &mut self,
+        direction: StageDirection,
+        event_loop_window_target: &EventLoopWindowTarget<MyEvent>,
+    ) {
+        info!("following stage direction {direction:?}");
+        match direction {
+            StageDirection::Push(name) => {
+                let Some(next) = self.data.play.playable_id(&name) else {
+                    warn!("no playable called {name} to push");
+                    return;
+                };
+                if let Some(current) = self.state.current_playable {
+                    if current == next {
+                        warn!("{name} is already on stage");
+                        return;
+                    }
+                    self.exit_playable(current);
+                    let camera = self.state.cur_camera.clone();
+                    self.state.playable_stack.push((current, camera));
+                }
+                self.stage_playable(next);
+            }
+            StageDirection::Pop => {
+                let Some((previous, camera)) = self.state.playable_stack.pop() else {
+                    warn!("nothing underneath the current playable to go back to");
+                    return;
+                };
+                if let Some(current) = self.state.current_playable {
+                    self.strike_playable(current);
+                }
+                self.stage_playable(previous);
+                if camera.is_some() {
+                    self.state.cur_camera = camera;
+                }
+            }
+            StageDirection::Replace(name) => {
+                let Some(next) = self.data.play.playable_id(&name) else {
+                    warn!("no playable called {name} to replace the current one with");
+                    return;
+                };
+                if let Some(current) = self.state.current_playable {
+                    self.strike_playable(current);
+                }
+                self.stage_playable(next);
+            }
+            StageDirection::Exeunt => {
+                // nothing after this gets a chance to go on
+                self.state.stage_directions.clear();
+                self.bring_down_the_curtain(event_loop_window_target);
+            }
+            StageDirection::SaveTo(slot) => {
+                if let Err(e) = self.save_to_slot(&slot) {
+                    warn!("couldn't save to {slot}: {e}");
+                }
+            }
+            StageDirection::LoadFrom(slot) => {
+                if let Err(e) = self.load_from_slot(&slot) {
+                    warn!("couldn't load {slot}: {e}");
+                }
+            }
+        }
+    }
+
+    /// Wait for the runtime to finish what it's doing and stop the event loop.
+    pub(crate) fn bring_down_the_curtain(
+        &mut self,
+        event_loop_window_target: &EventLoopWindowTarget<MyEvent>,
+    ) {
+        if let Some(path) = &self.settings.record_input {
+            self.state.save_recording(path);
+        }
+        if let Some(rts) = self.rts.take() {
+            rts.shutdown_timeout(Duration::from_secs(10));
+        }
+        event_loop_window_target.exit();
+    }
+
+    /// Make `playable_id` the current playable, implementing it first if it isn't already.
+    pub(crate) fn stage_playable(&mut self, playable_id: Uuid) {
+        self.rig_playable(playable_id);
+        let Some(playable) = self.data.play.playables.get_mut(&playable_id) else {
+            warn!("tried to stage unknown playable {playable_id}");
+            return;
+        };
+        let state = &mut self.state;
+
+        // Set camera location data
+        if let Definitions::SceneDefinition(definition) = playable.playable_definition() {
+            let start_cam = definition.start_cam.clone();
+            state.camera_controller = definition.camera_controller.clone();
+            state.cur_camera = match playable.playable_implementation() {
+                Some(Implementations::SceneImplementation(implementation)) => {
+                    implementation.cameras.get(&start_cam).cloned()
+                }
+                _ => None,
+            };
+            if state.cur_camera.is_none() {
+                info!("no {start_cam} camera yet, it might come with the stage");
+            }
+        }
+        // a move or path under way belonged to the last scene
+        state.camera_move = None;
+        state.camera_rail = None;
+        state.clear_hover();
+        state.pick_requested = false;
+        state.dialogue = None;
+        state.current_playable = Some(playable_id);
+        playable.playable_on_enter(state);
+    }
+
+    /// A stage finished loading: put it up and cast whatever cameras came with it, starting from
+    /// one of them if the scene is waiting for its start camera.
+    pub(crate) fn put_up_stage(&mut self, stage: LoadedStage3D) {
+        let LoadedStage3D {
+            name,
+            playable_id,
+            scene,
+            instance,
+            colliders,
+            cameras: stage_cameras,
+        } = stage;
+        let stage = AstinkScene::Loaded((name, playable_id, (scene, instance, stage_cameras)));
+        self.hang_stage(playable_id, stage, Some(colliders));
+    }
+    /// A stage that won't load: a scene still waiting for it goes on without one, one that already
+    /// has a stage up keeps it.
+    pub(crate) fn go_on_without_stage(&mut self, playable_id: Uuid) {
+        let waiting = self
+            .data
+            .play
+            .playables
+            .get_mut(&playable_id)
+            .is_some_and(|playable| {
+                matches!(
+                    playable.playable_implementation(),
+                    Some(Implementations::SceneImplementation(SceneImplementation {
+                        stage3d: AstinkScene::Loading,
+                        ..
+                    }))
+                )
+            });
+        if waiting {
+            self.hang_stage(playable_id, AstinkScene::Missing, None);
+        }
+    }
+    fn hang_stage(&mut self, playable_id: Uuid, stage: AstinkScene, colliders: Option<Colliders>) {
+        let Some(playable) = self.data.play.playables.get_mut(&playable_id) else {
+            return;
+        };
+        let Definitions::SceneDefinition(definition) = playable.playable_definition() else {
+            return;
+        };
+        let cameras = cast_cameras(definition, &stage);
+        let start_cam = definition.start_cam.clone();
+        let Some(Implementations::SceneImplementation(implementation)) =
+            playable.playable_implementation()
+        else {
+            info!("a stage finished loading after its playable was struck");
+            return;
+        };
+        implementation.stage3d = stage;
+        implementation.colliders = colliders;
+        implementation.cameras = cameras;
+        if self.state.current_playable != Some(playable_id) || self.state.cur_camera.is_some() {
+            return;
+        }
+        match implementation.cameras.get(&start_cam) {
+            Some(camera) => self.state.cur_camera = Some(camera.clone()),
+            None => warn!("there's no {start_cam} camera in the scene file or on the stage"),
+        }
+    }
+
+    /// Implement `playable_id` without putting it on stage, unless it already is implemented.
+    pub(crate) fn rig_playable(&mut self, playable_id: Uuid) {
+        let Some(playable) = self.data.play.playables.get_mut(&playable_id) else {
+            return;
+        };
+        if playable.playable_implementation().is_some() {
+            return;
+        }
+        let state = &self.state;
+        playable.implement_playable(
+            &self.settings,
+            state.event_loop_proxy.as_ref().unwrap(),
+            Arc::clone(state.renderer.as_ref().unwrap()),
+            Arc::clone(state.routines.as_ref().unwrap()),
+            self.rts.as_ref().unwrap(),
+            Arc::clone(state.orchestra.as_ref().unwrap()),
+            self.user_data.clone(),
+        );
+    }
+
+    fn exit_playable(&mut self, playable_id: Uuid) {
+        if let Some(playable) = self.data.play.playables.get_mut(&playable_id) {
+            playable.playable_on_exit(&mut self.state);
+        }
+        // keys held down as it went don't carry over into the next one
+        self.state.input_status.swallow_held();
+    }
+
+    /// Take the playable off stage for good: its renderer objects go away with the
+    /// implementation and whatever it was playing gets stopped.
+    pub(crate) fn strike_playable(&mut self, playable_id: Uuid) {
+        self.exit_playable(playable_id);
+        if let Some(orchestra) = &self.state.orchestra {
+            orchestra.stop_playable(playable_id);
+        }
+        // the loading curtain has to stay up for the next playable to load behind
+        let is_loading_curtain = self.data.play.loading_curtain == Some(playable_id);
+        if let Some(playable) = self.data.play.playables.get_mut(&playable_id) {
+            if !is_loading_curtain {
+                info!("striking {}", playable.playable_name());
+                if let (Some(Implementations::CurtainImplementation(curtain)), Some(egui_routine)) = (
+                    playable.playable_implementation().as_ref(),
+                    self.state.egui_routine.as_mut(),
+                ) {
+                    curtain.free_textures(egui_routine);
+                }
+                *playable.playable_implementation() = None;
+            }
+        }
+        if self.state.current_playable == Some(playable_id) {
+            self.state.current_playable = None;
+        }
+    }
+}
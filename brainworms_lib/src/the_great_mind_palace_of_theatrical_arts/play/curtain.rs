@@ -1,15 +1,331 @@
+// the 2d playables: menus and loading screens, painted with egui instead of put on a stage
 use std::{collections::HashMap, sync::Arc};
 
+use brainworms_arson::egui::{
+    self, pos2, vec2, Align2, Color32, Context, LayerId, Rect, TextureHandle, TextureOptions,
+};
+use glam::Vec2;
+use inox2d::formats::inp::parse_inp;
+use log::{info, warn};
 use parking_lot::Mutex;
+use rend3::Renderer;
+use serde::{Deserialize, Serialize};
+use tokio::runtime::Runtime;
+use wgpu::TextureFormat;
 
-use super::scene::actors::{ActressDefinition, AstinkSprite};
+use crate::theater::play::backstage::plumbing::asset_loader::{
+    load_progress, AssetLoader, AssetPath, LoadTicket,
+};
 
-#[derive(Debug, Default)]
+use super::scene::actors::ActressDefinition;
+
+const PUPPET_TEXTURE_SIZE: u32 = 2048;
+
+/// Backgrounds are `(name, directory)` of png files, drawn full screen in order.
+/// Actors are placed in screen space: the x and y of their translation are fractions of the
+/// screen width and height, `size` is a fraction of the screen height.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct CurtainDefinition {
+    #[serde(default)]
     pub backgrounds: Vec<(String, String)>,
+    #[serde(default)]
     pub actors: Vec<ActressDefinition>,
 }
 
+pub enum AstinkBackdrop {
+    Loading,
+    Loaded((String, egui::ColorImage)),
+    Hung(TextureHandle),
+}
+
+pub struct Puppet {
+    pub inox_renderer: inox2d_wgpu::Renderer,
+    pub inox_model: inox2d::model::Model,
+    pub texture_wgpu: wgpu::Texture,
+    pub texture_wgpu_view: wgpu::TextureView,
+    /// set once the texture is registered with the egui renderer
+    pub egui_texture: Option<egui::TextureId>,
+    /// centre, as fractions of the screen
+    pub position: Vec2,
+    /// as a fraction of the screen height
+    pub size: f32,
+}
+pub enum AstinkPuppet {
+    Loading,
+    Loaded(Puppet),
+}
+
 pub struct CurtainImplementation {
-    pub actresses: HashMap<String, Arc<Mutex<AstinkSprite>>>,
+    pub backgrounds: Vec<Arc<Mutex<AstinkBackdrop>>>,
+    pub actresses: HashMap<String, Arc<Mutex<AstinkPuppet>>>,
+}
+
+/// What the player picked on a curtain's menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurtainCall {
+    NewGame,
+    Continue,
+    Save,
+    Settings,
+    Quit,
+}
+
+/// Start loading everything the curtain shows. The pieces show up as they finish loading.
+pub fn implement_curtain(
+    definition: &CurtainDefinition,
+    renderer: Arc<Renderer>,
+    rts: &Runtime,
+) -> CurtainImplementation {
+    let backgrounds = definition
+        .backgrounds
+        .iter()
+        .map(|(name, directory)| {
+            let backdrop = Arc::new(Mutex::new(AstinkBackdrop::Loading));
+            rts.spawn(load_backdrop(
+                name.clone(),
+                directory.clone(),
+                Arc::clone(&backdrop),
+            ));
+            backdrop
+        })
+        .collect();
+    let actresses = definition
+        .actors
+        .iter()
+        .map(|actress| {
+            let puppet = Arc::new(Mutex::new(AstinkPuppet::Loading));
+            rts.spawn(create_puppet(
+                actress.clone(),
+                Arc::clone(&renderer),
+                Arc::clone(&puppet),
+            ));
+            (actress.name.clone(), puppet)
+        })
+        .collect();
+    CurtainImplementation {
+        backgrounds,
+        actresses,
+    }
+}
+
+async fn load_backdrop(name: String, directory: String, backdrop: Arc<Mutex<AstinkBackdrop>>) {
+    let _ticket = LoadTicket::take();
+    let path = format!("{}/{}.png", directory, name);
+    let loader = AssetLoader::default();
+    let bytes = match loader.get_asset(AssetPath::Internal(&path)).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!("background {name} stays rolled up: {e}");
+            return;
+        }
+    };
+    let image = match image::load_from_memory(&bytes) {
+        Ok(image) => image.to_rgba8(),
+        Err(e) => {
+            warn!("background {name} stays rolled up: {e}");
+            return;
+        }
+    };
+    let size = [image.width() as usize, image.height() as usize];
+    let image = egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw());
+    *backdrop.lock() = AstinkBackdrop::Loaded((name, image));
+}
+
+async fn create_puppet(
+    ActressDefinition {
+        name,
+        directory,
+        transform,
+        size,
+    }: ActressDefinition,
+    renderer: Arc<Renderer>,
+    puppet: Arc<Mutex<AstinkPuppet>>,
+) {
+    let _ticket = LoadTicket::take();
+    let path = format!("{}/{}.inp", &directory, name);
+    let loader = AssetLoader::default();
+    let inox_model = match loader.get_asset(AssetPath::Internal(&path)).await {
+        Ok(data) => match parse_inp(data.as_slice()) {
+            Ok(model) => model,
+            Err(e) => {
+                warn!("{name} missed her cue: {e}");
+                return;
+            }
+        },
+        Err(e) => {
+            warn!("{name} missed her cue: {e}");
+            return;
+        }
+    };
+    let format = TextureFormat::Bgra8Unorm;
+    let mut inox_renderer = inox2d_wgpu::Renderer::new(
+        &renderer.device,
+        &renderer.queue,
+        format,
+        &inox_model,
+        glam::UVec2::splat(PUPPET_TEXTURE_SIZE),
+    );
+    inox_renderer.camera.scale = glam::Vec2::splat(1.0);
+    let texture_wgpu = renderer.device.create_texture(&wgpu::TextureDescriptor {
+        size: wgpu::Extent3d {
+            width: PUPPET_TEXTURE_SIZE,
+            height: PUPPET_TEXTURE_SIZE,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        // drawn by inox, sampled by egui
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        label: Some("curtain puppet texture"),
+        view_formats: &[format],
+    });
+    let texture_wgpu_view = texture_wgpu.create_view(&wgpu::TextureViewDescriptor::default());
+    let translation = transform.w_axis;
+    *puppet.lock() = AstinkPuppet::Loaded(Puppet {
+        inox_renderer,
+        inox_model,
+        texture_wgpu,
+        texture_wgpu_view,
+        egui_texture: None,
+        position: glam::vec2(translation.x, translation.y),
+        size,
+    });
+    info!("{name} is behind the curtain");
+}
+
+/// Animate and render the curtain's puppets, before the chorus paints them.
+pub(crate) fn draw_curtain(
+    implementation: &CurtainImplementation,
+    renderer: &Renderer,
+    egui_routine: &mut rend3_egui::EguiRenderRoutine,
+    t: f32,
+    dt: f32,
+) {
+    for puppet in implementation.actresses.values() {
+        let mut puppet = puppet.lock();
+        let AstinkPuppet::Loaded(ref mut puppet) = *puppet else {
+            continue;
+        };
+        {
+            let puppet = &mut puppet.inox_model.puppet;
+            puppet.begin_set_params();
+            puppet.set_named_param("Head:: Yaw-Pitch", glam::vec2(t.cos(), t.sin()));
+            puppet.end_set_params(dt);
+        }
+        puppet.inox_renderer.render(
+            &renderer.queue,
+            &renderer.device,
+            &puppet.inox_model.puppet,
+            &puppet.texture_wgpu_view,
+        );
+        if puppet.egui_texture.is_none() {
+            puppet.egui_texture = Some(egui_routine.internal.register_native_texture(
+                &renderer.device,
+                &puppet.texture_wgpu_view,
+                wgpu::FilterMode::Linear,
+            ));
+        }
+    }
+}
+
+impl CurtainImplementation {
+    /// Hand the puppets' textures back to the egui renderer, it keeps them until told otherwise.
+    /// The backdrops' handles free themselves.
+    pub(crate) fn free_textures(&self, egui_routine: &mut rend3_egui::EguiRenderRoutine) {
+        for puppet in self.actresses.values() {
+            if let AstinkPuppet::Loaded(puppet) = &mut *puppet.lock() {
+                if let Some(texture) = puppet.egui_texture.take() {
+                    egui_routine.internal.free_texture(&texture);
+                }
+            }
+        }
+    }
+    /// Paint the backgrounds and the puppets behind every egui window.
+    pub fn paint(&self, egui_ctx: &Context) {
+        let painter = egui_ctx.layer_painter(LayerId::background());
+        let screen = egui_ctx.screen_rect();
+        let uv = Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0));
+        for backdrop in &self.backgrounds {
+            let mut backdrop = backdrop.lock();
+            if let AstinkBackdrop::Loaded((name, image)) = &*backdrop {
+                let handle =
+                    egui_ctx.load_texture(name.clone(), image.clone(), TextureOptions::LINEAR);
+                *backdrop = AstinkBackdrop::Hung(handle);
+            }
+            if let AstinkBackdrop::Hung(handle) = &*backdrop {
+                painter.image(handle.id(), screen, uv, Color32::WHITE);
+            }
+        }
+        for puppet in self.actresses.values() {
+            let puppet = puppet.lock();
+            let AstinkPuppet::Loaded(Puppet {
+                egui_texture: Some(texture),
+                position,
+                size,
+                ..
+            }) = &*puppet
+            else {
+                continue;
+            };
+            let centre = screen.min + vec2(position.x, position.y) * screen.size();
+            let side = size * screen.height();
+            painter.image(
+                *texture,
+                Rect::from_center_size(centre, vec2(side, side)),
+                uv,
+                Color32::WHITE,
+            );
+        }
+    }
+}
+
+/// The usual title menu. `can_continue` greys out "continue" when there's nothing to go back to,
+/// "save" only shows up when `can_save`.
+pub fn curtain_menu(egui_ctx: &Context, can_continue: bool, can_save: bool) -> Option<CurtainCall> {
+    let mut call = None;
+    egui::Window::new("menu")
+        .title_bar(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(egui_ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                if ui.button("new game").clicked() {
+                    call = Some(CurtainCall::NewGame);
+                }
+                if ui
+                    .add_enabled(can_continue, egui::Button::new("continue"))
+                    .clicked()
+                {
+                    call = Some(CurtainCall::Continue);
+                }
+                if can_save && ui.button("save").clicked() {
+                    call = Some(CurtainCall::Save);
+                }
+                if ui.button("settings").clicked() {
+                    call = Some(CurtainCall::Settings);
+                }
+                if ui.button("quit").clicked() {
+                    call = Some(CurtainCall::Quit);
+                }
+            });
+        });
+    call
+}
+
+/// A progress bar along the bottom of the screen for as long as anything is loading.
+pub fn show_load_progress(egui_ctx: &Context) {
+    let Some((finished, started)) = load_progress() else {
+        return;
+    };
+    egui::TopBottomPanel::bottom("load progress")
+        .show_separator_line(false)
+        .show(egui_ctx, |ui| {
+            ui.add(
+                egui::ProgressBar::new(finished as f32 / started as f32)
+                    .text(format!("loading {finished}/{started}")),
+            );
+        });
 }
@@ -1,14 +1,37 @@
-use std::{mem::take, sync::Arc};
+use std::{collections::HashMap, mem::take, sync::Arc, time::Duration};
 
-use baudio::{audio_router_thread, AudioCommand, JingleRegistry};
+use baudio::{
+    audio_router_thread, AudioCommand, JingleName, JingleRegistry, SoundGroup,
+    TicketedAudioRequestData as TARD,
+};
 use brainworms_farting_noises as baudio;
 
+use brainworms_arson::nanorand::{self, Rng};
+use log::warn;
 use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
 use tokio::{runtime::Handle, sync::mpsc::UnboundedSender};
+use uuid::Uuid;
 type Generation = u64;
+
+// how long a restored jingle gets to show up in the registry before we give up on it
+const RESTORE_PATIENCE: Duration = Duration::from_secs(10);
+
+/// What the orchestra was up to, as much of it as a save file needs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioState {
+    pub bgm_volume: Option<f32>,
+    pub sfx_volume: Option<f32>,
+    pub voice_volumes: HashMap<String, f32>,
+    pub playing: Vec<JingleName>,
+}
 pub struct Orchestra {
     handler: (Generation, Option<UnboundedSender<AudioCommand>>),
     jingle_registry: Arc<Mutex<JingleRegistry>>,
+    // tickets that were sent a Play and haven't been stopped yet, with the playable that took
+    // them if there was one
+    playing: Mutex<HashMap<(JingleName, Uuid), Option<Uuid>>>,
     rth: Handle,
 }
 impl Orchestra {
@@ -17,6 +40,7 @@ impl Orchestra {
         let mut me = Self {
             handler: (0, None),
             jingle_registry,
+            playing: Mutex::new(HashMap::new()),
             rth,
         };
         me.replace_worker();
@@ -36,11 +60,131 @@ impl Orchestra {
         old_tx.map(|old_tx| old_tx.send(AudioCommand::Die));
         self.handler = (gen, Some(tx));
     }
+    /// Tickets played this way belong to nobody and keep going until they're stopped, whoever
+    /// gets struck.
     pub fn send_cmd(&self, cmd: AudioCommand) {
+        self.send(None, cmd);
+    }
+    /// The same as [`send_cmd`](Self::send_cmd), but whatever tickets it plays get stopped when
+    /// `playable` is struck.
+    pub fn send_cmd_for(&self, playable: Uuid, cmd: AudioCommand) {
+        self.send(Some(playable), cmd);
+    }
+    fn send(&self, playable: Option<Uuid>, cmd: AudioCommand) {
+        match &cmd {
+            AudioCommand::Play(TARD::Targeted(name, ticket)) => {
+                self.playing
+                    .lock()
+                    .insert((name.clone(), *ticket), playable);
+            }
+            AudioCommand::Stop(TARD::Targeted(name, ticket)) => {
+                self.playing.lock().remove(&(name.clone(), *ticket));
+            }
+            _ => {}
+        }
         let _ = self.handler.1.as_ref().unwrap().send(cmd);
     }
+    /// Stop every ticket that is still playing, whoever took it.
+    pub fn stop_all(&self) {
+        let playing: Vec<_> = self.playing.lock().drain().map(|(key, _)| key).collect();
+        self.stop_tickets(playing);
+    }
+    /// Stop the tickets `playable` took, when it's struck. Everyone else's keep going.
+    pub fn stop_playable(&self, playable: Uuid) {
+        let mut struck = vec![];
+        self.playing.lock().retain(|key, owner| {
+            let keep = *owner != Some(playable);
+            if !keep {
+                struck.push(key.clone());
+            }
+            keep
+        });
+        self.stop_tickets(struck);
+    }
+    fn stop_tickets(&self, tickets: Vec<(JingleName, Uuid)>) {
+        for (name, ticket) in tickets {
+            let _ = self
+                .handler
+                .1
+                .as_ref()
+                .unwrap()
+                .send(AudioCommand::Stop(TARD::Targeted(name, ticket)));
+        }
+    }
     pub fn is_registered(&self, name: &str) -> bool {
         let registry = self.jingle_registry.lock();
         registry.jingles.contains_key(name)
     }
+    /// Start the registered jingle `name` on a fresh ticket for `playable`, handing the ticket back
+    /// so it can be stopped. Nothing if it isn't registered.
+    pub fn play_jingle(&self, playable: Uuid, name: &str) -> Option<Uuid> {
+        if !self.is_registered(name) {
+            return None;
+        }
+        let mut rng = nanorand::tls_rng();
+        let ticket =
+            uuid::Builder::from_random_bytes([0; 16].map(|_| rng.generate::<u8>())).into_uuid();
+        self.send_cmd_for(
+            playable,
+            AudioCommand::Play(TARD::Targeted(name.to_owned(), ticket)),
+        );
+        Some(ticket)
+    }
+    pub fn audio_state(&self) -> AudioState {
+        let registry = self.jingle_registry.lock();
+        let mut playing: Vec<_> = self
+            .playing
+            .lock()
+            .keys()
+            .map(|(name, _)| name.clone())
+            .collect();
+        playing.sort();
+        playing.dedup();
+        AudioState {
+            bgm_volume: registry.volume.get(&SoundGroup::BGM).copied(),
+            sfx_volume: registry.volume.get(&SoundGroup::SFX).copied(),
+            voice_volumes: registry
+                .volume
+                .iter()
+                .filter_map(|(group, volume)| match group {
+                    SoundGroup::Voice(voice) => Some((voice.clone(), *volume)),
+                    _ => None,
+                })
+                .collect(),
+            playing,
+        }
+    }
+    /// Put the volumes back and start whatever was playing again for `playable`, once it has
+    /// registered it.
+    pub fn restore_audio_state(self: &Arc<Self>, audio_state: &AudioState, playable: Uuid) {
+        let volumes = [
+            (SoundGroup::BGM, audio_state.bgm_volume),
+            (SoundGroup::SFX, audio_state.sfx_volume),
+        ]
+        .into_iter()
+        .filter_map(|(group, volume)| Some((group, volume?)))
+        .chain(
+            audio_state
+                .voice_volumes
+                .iter()
+                .map(|(voice, volume)| (SoundGroup::Voice(voice.clone()), *volume)),
+        );
+        for (group, volume) in volumes {
+            self.send_cmd(AudioCommand::SetVolume(group, volume));
+        }
+        for name in audio_state.playing.clone() {
+            let orchestra = Arc::clone(self);
+            self.rth.spawn(async move {
+                let started = tokio::time::Instant::now();
+                while !orchestra.is_registered(&name) {
+                    if started.elapsed() > RESTORE_PATIENCE {
+                        warn!("{name} never showed up, not playing it again");
+                        return;
+                    }
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+                orchestra.play_jingle(playable, &name);
+            });
+        }
+    }
 }
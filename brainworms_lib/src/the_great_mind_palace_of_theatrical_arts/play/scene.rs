@@ -2,36 +2,81 @@ use std::{collections::HashMap, sync::Arc};
 
 use glam::{Mat3A, Vec3A};
 use parking_lot::Mutex;
-use rend3::Renderer;
+use parry3d::query::Ray;
+use rend3::{types::CameraProjection, Renderer};
+use serde::{Deserialize, Serialize};
 use tokio::runtime::Runtime;
 use uuid::Uuid;
-use winit::event_loop::EventLoop;
+use winit::event_loop::EventLoopProxy;
 
 use crate::{theater::basement::cla::GameProgrammeSettings, MyEvent};
 
-use self::{actors::ActressDefinition, stage3d::Colliders};
+use self::{
+    actors::ActressDefinition,
+    camera_paths::CameraPathDefinition,
+    hovering::HoverDefinition,
+    interactions::Interaction,
+    props::PropDefinition,
+    stage3d::{
+        camera_controllers::CameraController,
+        collider_building::{ColliderShape, ColliderSpec},
+        picking::{CollisionGroups, PickFilter, PickHit},
+        Colliders, StageCameras,
+    },
+};
 
 use super::{
     backstage::plumbing::DefaultRoutines, orchestra::Orchestra, Definitions, Implementations,
 };
 
 pub mod actors;
+pub mod camera_moves;
+pub mod camera_paths;
 pub mod chorus;
 pub mod definitions;
+pub mod hovering;
+pub mod interactions;
 pub mod props;
 pub mod stage3d;
-#[derive(Debug, Default, Clone)]
+pub const DEFAULT_VFOV: f32 = 60.0;
+pub const DEFAULT_NEAR: f32 = 0.1;
+/// how far in front of and behind an orthographic camera things still get drawn
+pub const ORTHO_DEPTH: f32 = 1000.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct CamInfo {
     pub x: f32,
     pub y: f32,
     pub z: f32,
     pub pitch: f32,
     pub yaw: f32,
+    /// vertical field of view in degrees
+    #[serde(default = "default_vfov")]
+    pub vfov: f32,
+    #[serde(default = "default_near")]
+    pub near: f32,
+    /// how much of the world fits top to bottom, for top-down views and the like. Perspective
+    /// when there isn't one
+    #[serde(default)]
+    pub ortho_size: Option<f32>,
+}
+fn default_vfov() -> f32 {
+    DEFAULT_VFOV
+}
+fn default_near() -> f32 {
+    DEFAULT_NEAR
+}
+impl Default for CamInfo {
+    fn default() -> Self {
+        Self::from_arr(&[0.0; 5])
+    }
 }
 impl CamInfo {
     pub fn location(&self) -> Vec3A {
         Vec3A::new(self.x, self.y, self.z)
     }
+    /// Position, pitch and yaw, with the default projection.
     pub fn from_arr(arr: &[f32; 5]) -> Self {
         Self {
             x: arr[0],
@@ -39,6 +84,40 @@ impl CamInfo {
             z: arr[2],
             pitch: arr[3],
             yaw: arr[4],
+            vfov: DEFAULT_VFOV,
+            near: DEFAULT_NEAR,
+            ortho_size: None,
+        }
+    }
+    /// What the renderer wants to know about the lens, for a window `aspect_ratio` wide per high.
+    pub fn projection(&self, aspect_ratio: f32) -> CameraProjection {
+        match self.ortho_size {
+            Some(size) => CameraProjection::Orthographic {
+                size: Vec3A::new(size * aspect_ratio, size, ORTHO_DEPTH * 2.0),
+            },
+            None => CameraProjection::Perspective {
+                vfov: self.vfov,
+                near: self.near,
+            },
+        }
+    }
+    /// How much bigger things look than through a default camera.
+    pub fn zoom(&self) -> f32 {
+        match self.ortho_size {
+            Some(size) => 1.0 / size,
+            None => (DEFAULT_VFOV.to_radians() / 2.0).tan() / (self.vfov.to_radians() / 2.0).tan(),
+        }
+    }
+    /// Make things look `zoom` times bigger than through a default camera.
+    pub fn set_zoom(&mut self, zoom: f32) {
+        match self.ortho_size.as_mut() {
+            Some(size) => *size = 1.0 / zoom,
+            None => {
+                self.vfov = ((DEFAULT_VFOV.to_radians() / 2.0).tan() / zoom)
+                    .atan()
+                    .to_degrees()
+                    * 2.0
+            }
         }
     }
     pub fn set_location(&mut self, x: f32, y: f32, z: f32) {
@@ -57,13 +136,68 @@ pub struct Camera {
     pub info: CamInfo,
     pub rotation: Mat3A,
 }
-#[derive(Default, Debug)]
+/// What a scene is made of, usually read from a scene file with
+/// [`definitions::load_scene_definition`].
+#[derive(Default, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SceneDefinition {
+    /// (name, directory) of the glTF stage
     pub stage: (String, String),
+    #[serde(default)]
     pub actors: Vec<ActressDefinition>,
-    pub props: Vec<(String, String)>,
+    #[serde(default)]
+    pub props: Vec<PropDefinition>,
+    /// either in `cameras` or a camera node on the stage
     pub start_cam: String,
+    /// on top of the stage's own cameras, replacing any with the same name
+    #[serde(default)]
     pub cameras: HashMap<String, CamInfo>,
+    /// for cutscenes, see [`camera_paths`]
+    #[serde(default)]
+    pub camera_paths: HashMap<String, CameraPathDefinition>,
+    /// how the player gets the camera about
+    #[serde(default)]
+    pub camera_controller: CameraController,
+    /// names of the stage nodes that get colliders for picking
+    #[serde(default)]
+    pub colliders: Vec<String>,
+    /// stage nodes that can be walked on, see [`stage3d::character_controller`]
+    #[serde(default)]
+    pub walkable: Vec<String>,
+    /// stage nodes that get in the way of walking
+    #[serde(default)]
+    pub blocking: Vec<String>,
+    /// cheaper shapes for any of the above that don't need every triangle, see
+    /// [`stage3d::collider_building`]
+    #[serde(default)]
+    pub collider_shapes: HashMap<String, ColliderShape>,
+    /// what the mouse being over a collider does, by collider name
+    #[serde(default)]
+    pub hover: HashMap<String, HoverDefinition>,
+    /// what picking a collider does, by collider name
+    #[serde(default)]
+    pub interactions: HashMap<String, Interaction>,
+}
+impl SceneDefinition {
+    /// what `load_stage3d` wants to know about which nodes to build colliders for, and what
+    /// they're for
+    pub fn collider_ids(&self) -> HashMap<String, ColliderSpec> {
+        let mut ids = HashMap::new();
+        for (names, groups) in [
+            (&self.colliders, CollisionGroups::PICKABLE),
+            (&self.walkable, CollisionGroups::WALKABLE),
+            (&self.blocking, CollisionGroups::BLOCKING),
+        ] {
+            for name in names {
+                let entry = ids.entry(name.to_owned()).or_insert(ColliderSpec {
+                    groups: CollisionGroups::NONE,
+                    shape: self.collider_shapes.get(name).copied().unwrap_or_default(),
+                });
+                entry.groups = entry.groups | groups;
+            }
+        }
+        ids
+    }
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -76,21 +210,48 @@ pub enum AstinkScene {
             (
                 rend3_gltf::LoadedGltfScene,
                 rend3_gltf::GltfSceneInstance,
-                Colliders,
+                StageCameras,
             ),
         ),
     ),
     #[default]
     Loading,
+    /// didn't load, the scene goes on without one
+    Missing,
+}
+/// A stage that's done loading, on its way over to the playable it was loaded for.
+pub struct LoadedStage3D {
+    pub name: String,
+    pub playable_id: Uuid,
+    pub scene: rend3_gltf::LoadedGltfScene,
+    pub instance: rend3_gltf::GltfSceneInstance,
+    pub colliders: Colliders,
+    pub cameras: StageCameras,
 }
 #[derive(Default)]
 pub struct SceneImplementation {
     pub stage3d: AstinkScene,
+    /// the stage's, kept off the rend3 scene so there's something to pick without a renderer
+    pub colliders: Option<Colliders>,
     pub actresses: HashMap<String, Arc<Mutex<actors::AstinkSprite>>>,
     pub props: HashMap<String, props::Prop>,
     pub cameras: HashMap<String, Camera>,
     //    script: String, // I'm really kinda stuck on this chicken and egg problem with script <-> actual game logic
 }
+impl SceneImplementation {
+    /// The nearest thing `ray` goes into that gets past `filter`, on the stage or one of the
+    /// props, with the colliders it's in.
+    pub fn pick(&self, ray: &Ray, filter: &PickFilter) -> Option<(PickHit, &Colliders)> {
+        self.colliders
+            .iter()
+            .chain(self.props.values().filter_map(|p| p.colliders()))
+            .filter_map(|colliders| {
+                let hit = colliders.cast_ray(ray, filter).into_iter().next()?;
+                Some((hit, colliders))
+            })
+            .min_by(|(a, _), (b, _)| a.distance.total_cmp(&b.distance))
+    }
+}
 pub trait Scenic<UserData> {
     fn scene_uuid(&self) -> Uuid;
     fn scene_name(&self) -> &str;
@@ -98,7 +259,7 @@ pub trait Scenic<UserData> {
     fn implement_scene(
         &mut self,
         settings: &GameProgrammeSettings,
-        event_loop: &EventLoop<MyEvent>,
+        event_loop_proxy: &EventLoopProxy<MyEvent>,
         renderer: Arc<Renderer>,
         routines: Arc<DefaultRoutines>,
         rts: &Runtime,
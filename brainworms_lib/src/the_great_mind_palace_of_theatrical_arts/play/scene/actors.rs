@@ -4,6 +4,7 @@ use glam::uvec2;
 use inox2d::formats::inp::parse_inp;
 use parking_lot::Mutex;
 use rend3::{types::MipmapCount, Renderer};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use wgpu::TextureFormat;
 use winit::event_loop::EventLoopProxy;
@@ -11,14 +12,16 @@ use winit::event_loop::EventLoopProxy;
 use crate::{
     theater::{
         basement::quad_damage::create_quad,
-        play::backstage::plumbing::asset_loader::{AssetLoader, AssetPath},
+        play::backstage::plumbing::asset_loader::{AssetLoader, AssetPath, LoadTicket},
     },
     MyEvent, MyWinitEvent,
 };
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ActressDefinition {
     pub name: String,
     pub directory: String,
+    #[serde(with = "super::definitions::transform", default)]
     pub transform: glam::Mat4,
     pub size: f32,
 }
@@ -91,6 +94,7 @@ pub async fn create_actor(
     size: f32,
     sc_id: Uuid,
 ) {
+    let _ticket = LoadTicket::take();
     let path = format!("{}/{}.inp", &directory, name);
     let format = TextureFormat::Bgra8Unorm;
     let texture_size_uvec2 = glam::uvec2(8192, 8192); // we no longer care about the surface size for the sprite texture
@@ -0,0 +1,163 @@
+// gliding the camera over to one of the scene's named cameras instead of cutting to it
+use std::{
+    f32::consts::{PI, TAU},
+    time::Duration,
+};
+
+use log::warn;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    theater::{
+        basement::{easing::Easing, input_handling::InputContext},
+        play::{Implementations, Playable},
+    },
+    GameProgramme, GameProgrammeState,
+};
+
+use super::{stage3d::do_update_camera, CamInfo};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraMove {
+    /// the named camera being moved to
+    pub to: String,
+    pub duration: Duration,
+    pub easing: Easing,
+    pub elapsed: Duration,
+    /// where it started from and where it's going, once the named camera has been looked up
+    path: Option<(CamInfo, CamInfo)>,
+}
+
+/// Partway from `from` to `to`, turning whichever way round is shorter. Zooms along the way too,
+/// but switches between perspective and orthographic halfway.
+pub fn lerp_cam_info(from: &CamInfo, to: &CamInfo, t: f32) -> CamInfo {
+    let lerp = |a: f32, b: f32| a + (b - a) * t;
+    let yaw_delta = (to.yaw - from.yaw + PI).rem_euclid(TAU) - PI;
+    let ortho_size = match (from.ortho_size, to.ortho_size) {
+        (Some(a), Some(b)) => Some(lerp(a, b)),
+        _ if t < 0.5 => from.ortho_size,
+        _ => to.ortho_size,
+    };
+    CamInfo {
+        x: lerp(from.x, to.x),
+        y: lerp(from.y, to.y),
+        z: lerp(from.z, to.z),
+        pitch: lerp(from.pitch, to.pitch),
+        yaw: (from.yaw + yaw_delta * t).rem_euclid(TAU),
+        vfov: lerp(from.vfov, to.vfov),
+        near: lerp(from.near, to.near),
+        ortho_size,
+    }
+}
+
+impl<InputContextEnum: InputContext> GameProgrammeState<InputContextEnum> {
+    /// Glide the camera over to the current scene's camera called `name`. A move that's already
+    /// going gets interrupted and the new one starts from wherever it got to.
+    pub fn move_camera_to(&mut self, name: &str, duration: Duration, easing: Easing) {
+        self.camera_rail = None;
+        self.camera_move = Some(CameraMove {
+            to: name.to_owned(),
+            duration,
+            easing,
+            elapsed: Duration::ZERO,
+            path: None,
+        });
+    }
+    /// Zoom `zoom` times closer than the current camera is now, staying where it is. Arrives at
+    /// the camera it's already at, once it's done.
+    pub fn zoom_camera(&mut self, zoom: f32, duration: Duration, easing: Easing) {
+        let Some(cur_camera) = self.cur_camera.as_ref() else {
+            return;
+        };
+        let from = cur_camera.info.clone();
+        let mut to = from.clone();
+        to.set_zoom(from.zoom() * zoom);
+        self.camera_rail = None;
+        self.camera_move = Some(CameraMove {
+            to: cur_camera.name.clone(),
+            duration,
+            easing,
+            elapsed: Duration::ZERO,
+            path: Some((from, to)),
+        });
+    }
+    /// Leave the camera wherever the move got it to, handing the move back if there was one.
+    pub fn stop_camera_move(&mut self) -> Option<CameraMove> {
+        self.camera_move.take()
+    }
+    pub fn is_camera_moving(&self) -> bool {
+        self.camera_move.is_some()
+    }
+
+    /// Take the camera `dt` further along. Returns the name of the camera it was going to once it
+    /// gets there.
+    pub fn advance_camera_move(&mut self, dt: Duration) -> Option<String> {
+        let camera_move = self.camera_move.as_mut()?;
+        let cur_camera = self.cur_camera.as_mut()?;
+        // not looked up yet
+        let (from, to) = camera_move.path.as_ref()?;
+        camera_move.elapsed += dt;
+        let linear = if camera_move.duration.is_zero() {
+            1.0
+        } else {
+            (camera_move.elapsed.as_secs_f32() / camera_move.duration.as_secs_f32()).min(1.0)
+        };
+        cur_camera.info = lerp_cam_info(from, to, camera_move.easing.apply(linear));
+        if linear < 1.0 {
+            return None;
+        }
+        let arrived = self.camera_move.take()?.to;
+        cur_camera.name = arrived.clone();
+        Some(arrived)
+    }
+}
+
+impl<
+        PlayablesEnum: Playable<InputContextEnum, UserData> + 'static,
+        InputContextEnum: InputContext + 'static,
+        UserData: Default + Serialize + DeserializeOwned + 'static,
+    > GameProgramme<PlayablesEnum, InputContextEnum, UserData>
+{
+    /// Find where a camera move that was just asked for is going, among the current scene's
+    /// cameras. One going nowhere gets dropped.
+    pub(crate) fn rig_camera_move(&mut self) {
+        let state = &mut self.state;
+        let Some(camera_move) = state.camera_move.as_mut().filter(|m| m.path.is_none()) else {
+            return;
+        };
+        let target = state
+            .current_playable
+            .and_then(|id| self.data.play.playables.get_mut(&id))
+            .and_then(|playable| match playable.playable_implementation() {
+                Some(Implementations::SceneImplementation(implementation)) => implementation
+                    .cameras
+                    .get(&camera_move.to)
+                    .map(|camera| camera.info.clone()),
+                _ => None,
+            });
+        let from = state.cur_camera.as_ref().map(|camera| camera.info.clone());
+        match (from, target) {
+            (Some(from), Some(to)) => camera_move.path = Some((from, to)),
+            _ => {
+                warn!("no camera called {} to move to", camera_move.to);
+                state.camera_move = None;
+            }
+        }
+    }
+
+    /// Take the camera along its move or path by the frame's `frame_delta`, telling the current
+    /// playable when a move gets where it was going.
+    pub(crate) fn advance_camera(&mut self) {
+        let state = &mut self.state;
+        if let Some(arrived) = state.advance_camera_move(state.frame_delta) {
+            let current = state
+                .current_playable
+                .and_then(|id| self.data.play.playables.get_mut(&id));
+            if let Some(current) = current {
+                current.playable_on_camera_arrived(state, &arrived);
+            }
+        }
+        state.advance_camera_path(state.frame_delta);
+        do_update_camera(state);
+    }
+}
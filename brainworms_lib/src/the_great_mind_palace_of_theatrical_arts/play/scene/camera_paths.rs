@@ -0,0 +1,335 @@
+// cinematic rails: the camera following an authored path, from keyframes in the scene file or a
+// camera animated in Blender and exported with the stage
+use std::{collections::HashMap, time::Duration};
+
+use glam::{Quat, Vec3, Vec3A};
+use log::warn;
+use rend3_gltf::GltfLoadSettings;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    theater::{
+        basement::{easing::Easing, input_handling::InputContext},
+        play::{Definitions, Implementations, Playable},
+    },
+    GameProgramme, GameProgrammeState,
+};
+
+use super::{camera_moves::lerp_cam_info, stage3d::point_camera_at, AstinkScene, CamInfo};
+
+/// Keyframes for every animated node in a stage glb, by node name.
+pub type StageCameraPaths = HashMap<String, Vec<CameraKeyframe>>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CameraKeyframe {
+    /// seconds from the start of the path
+    pub at: f32,
+    pub cam: CamInfo,
+    /// somewhere to look at instead of `cam`'s pitch and yaw
+    #[serde(default)]
+    pub look_at: Option<[f32; 3]>,
+    /// how to get from here to the next keyframe
+    #[serde(default)]
+    pub easing: Easing,
+}
+
+/// A path as written in the scene file, either keyframes or a node in the stage glb.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CameraPathDefinition {
+    #[serde(default)]
+    pub keyframes: Vec<CameraKeyframe>,
+    /// an animated node in the stage glb to follow instead, like a camera exported from Blender
+    #[serde(default)]
+    pub stage_node: Option<String>,
+    /// somewhere to keep looking at all the way along, for keyframes that don't say otherwise
+    #[serde(default)]
+    pub look_at: Option<[f32; 3]>,
+    #[serde(default)]
+    pub looping: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraPath {
+    /// in order of `at`, never empty
+    keyframes: Vec<CameraKeyframe>,
+    pub looping: bool,
+}
+
+impl CameraPath {
+    /// `None` when there's nothing to follow.
+    pub fn new(
+        mut keyframes: Vec<CameraKeyframe>,
+        look_at: Option<[f32; 3]>,
+        looping: bool,
+    ) -> Option<Self> {
+        if keyframes.is_empty() {
+            return None;
+        }
+        keyframes.sort_by(|a, b| a.at.total_cmp(&b.at));
+        for keyframe in &mut keyframes {
+            keyframe.look_at = keyframe.look_at.or(look_at);
+        }
+        Some(Self { keyframes, looping })
+    }
+    pub fn keyframes(&self) -> &[CameraKeyframe] {
+        &self.keyframes
+    }
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f32(self.keyframes.last().unwrap().at.max(0.0))
+    }
+
+    /// Where the camera is `at` seconds in, going through the keyframes on a Catmull-Rom curve.
+    /// Looping paths go round again, the rest stop at either end.
+    pub fn sample(&self, at: f32) -> CamInfo {
+        let keys = &self.keyframes;
+        let (first, last) = (keys[0].at, keys[keys.len() - 1].at);
+        let at = if self.looping && last > first {
+            first + (at - first).rem_euclid(last - first)
+        } else {
+            at.clamp(first, last)
+        };
+        let Some(i) = keys.windows(2).position(|pair| at < pair[1].at) else {
+            let mut cam = keys[keys.len() - 1].cam.clone();
+            if let Some(target) = keys[keys.len() - 1].look_at {
+                point_camera_at(&mut cam, target.into());
+            }
+            return cam;
+        };
+        let (from, to) = (&keys[i], &keys[i + 1]);
+        let span = to.at - from.at;
+        let t = from.easing.apply(if span > 0.0 {
+            (at - from.at) / span
+        } else {
+            1.0
+        });
+        let before = &keys[i.saturating_sub(1)];
+        let after = keys.get(i + 2).unwrap_or(to);
+        let location = catmull_rom(
+            before.cam.location(),
+            from.cam.location(),
+            to.cam.location(),
+            after.cam.location(),
+            t,
+        );
+        let mut cam = lerp_cam_info(&from.cam, &to.cam, t);
+        cam.set_location(location.x, location.y, location.z);
+        let target = match (from.look_at, to.look_at) {
+            (Some(a), Some(b)) => Some(Vec3A::from(a).lerp(b.into(), t)),
+            (Some(a), None) | (None, Some(a)) => Some(a.into()),
+            (None, None) => None,
+        };
+        if let Some(target) = target {
+            point_camera_at(&mut cam, target);
+        }
+        cam
+    }
+}
+
+fn catmull_rom(p0: Vec3A, p1: Vec3A, p2: Vec3A, p3: Vec3A, t: f32) -> Vec3A {
+    let (t2, t3) = (t * t, t * t * t);
+    0.5 * (2.0 * p1
+        + (p2 - p0) * t
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+        + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+}
+
+/// The camera on a path, or waiting for the path to be found.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraRail {
+    /// the scene's name for the path
+    pub name: String,
+    pub elapsed: Duration,
+    pub paused: bool,
+    path: Option<CameraPath>,
+}
+impl CameraRail {
+    pub fn path(&self) -> Option<&CameraPath> {
+        self.path.as_ref()
+    }
+    /// Got to the end of a path that doesn't loop.
+    pub fn finished(&self) -> bool {
+        self.path
+            .as_ref()
+            .is_some_and(|path| !path.looping && self.elapsed >= path.duration())
+    }
+}
+
+impl<InputContextEnum: InputContext> GameProgrammeState<InputContextEnum> {
+    /// Put the camera on the current scene's path called `name`, from the start. Stops any
+    /// camera move or other path going.
+    pub fn play_camera_path(&mut self, name: &str) {
+        self.camera_move = None;
+        self.camera_rail = Some(CameraRail {
+            name: name.to_owned(),
+            elapsed: Duration::ZERO,
+            paused: false,
+            path: None,
+        });
+    }
+    pub fn pause_camera_path(&mut self) {
+        if let Some(rail) = self.camera_rail.as_mut() {
+            rail.paused = true;
+        }
+    }
+    pub fn resume_camera_path(&mut self) {
+        if let Some(rail) = self.camera_rail.as_mut() {
+            rail.paused = false;
+        }
+    }
+    /// Jump to `at` along the path, paused or not.
+    pub fn scrub_camera_path(&mut self, at: Duration) {
+        if let Some(rail) = self.camera_rail.as_mut() {
+            rail.elapsed = at;
+        }
+    }
+    /// Take the camera off the path, leaving it where it was.
+    pub fn stop_camera_path(&mut self) -> Option<CameraRail> {
+        self.camera_rail.take()
+    }
+
+    /// Take the camera `dt` further along its path, unless it's paused.
+    pub fn advance_camera_path(&mut self, dt: Duration) {
+        let Some(rail) = self.camera_rail.as_mut() else {
+            return;
+        };
+        let (Some(path), Some(cur_camera)) = (rail.path.as_ref(), self.cur_camera.as_mut()) else {
+            return;
+        };
+        if !rail.paused {
+            rail.elapsed += dt;
+        }
+        if !path.looping {
+            rail.elapsed = rail.elapsed.min(path.duration());
+        }
+        cur_camera.info = path.sample(rail.elapsed.as_secs_f32());
+    }
+}
+
+impl<
+        PlayablesEnum: Playable<InputContextEnum, UserData> + 'static,
+        InputContextEnum: InputContext + 'static,
+        UserData: Default + Serialize + DeserializeOwned + 'static,
+    > GameProgramme<PlayablesEnum, InputContextEnum, UserData>
+{
+    /// Find the path a camera rail that was just asked for is following, in the current scene's
+    /// definition or its stage. Paths off the stage wait for it to finish loading.
+    pub(crate) fn rig_camera_path(&mut self) {
+        let state = &mut self.state;
+        let Some(rail) = state.camera_rail.as_mut().filter(|r| r.path.is_none()) else {
+            return;
+        };
+        let Some(playable) = state
+            .current_playable
+            .and_then(|id| self.data.play.playables.get_mut(&id))
+        else {
+            return;
+        };
+        let definition = match playable.playable_definition() {
+            Definitions::SceneDefinition(definition) => {
+                definition.camera_paths.get(&rail.name).cloned()
+            }
+            _ => None,
+        };
+        let Some(definition) = definition else {
+            warn!("no camera path called {}", rail.name);
+            state.camera_rail = None;
+            return;
+        };
+        let keyframes = match &definition.stage_node {
+            None => Some(definition.keyframes),
+            Some(node) => match playable.playable_implementation() {
+                Some(Implementations::SceneImplementation(implementation)) => {
+                    let AstinkScene::Loaded((_, _, (_, _, stage_cameras))) =
+                        &implementation.stage3d
+                    else {
+                        return;
+                    };
+                    stage_cameras.paths.get(node).cloned()
+                }
+                _ => None,
+            },
+        };
+        match keyframes.and_then(|k| CameraPath::new(k, definition.look_at, definition.looping)) {
+            Some(path) => rail.path = Some(path),
+            None => {
+                warn!("camera path {} has nothing to follow", rail.name);
+                state.camera_rail = None;
+            }
+        }
+    }
+}
+
+/// Keyframes for every node in the glb that has its translation animated, with whatever its
+/// rotation is doing at the same times. Blender's cameras look down -Z like everything else.
+pub(crate) fn camera_paths_from_gltf(
+    document: &gltf::Document,
+    buffers: &[Vec<u8>],
+    settings: &GltfLoadSettings,
+) -> StageCameraPaths {
+    let mut out = StageCameraPaths::new();
+    for node in document.nodes() {
+        let Some(name) = node.name() else {
+            continue;
+        };
+        let mut translations: Option<Vec<(f32, Vec3)>> = None;
+        let mut rotations: Vec<(f32, Quat)> = vec![];
+        for channel in document
+            .animations()
+            .flat_map(|a| a.channels())
+            .filter(|c| c.target().node().index() == node.index())
+        {
+            let reader = channel.reader(|b| Some(&buffers[b.index()][..b.length()]));
+            let (Some(times), Some(outputs)) = (reader.read_inputs(), reader.read_outputs()) else {
+                continue;
+            };
+            match outputs {
+                gltf::animation::util::ReadOutputs::Translations(t) if translations.is_none() => {
+                    translations = Some(times.zip(t.map(Vec3::from)).collect());
+                }
+                gltf::animation::util::ReadOutputs::Rotations(r) if rotations.is_empty() => {
+                    rotations = times.zip(r.into_f32().map(Quat::from_array)).collect();
+                }
+                _ => {}
+            }
+        }
+        let Some(translations) = translations else {
+            continue;
+        };
+        let (_, resting, _) = node.transform().decomposed();
+        let resting = Quat::from_array(resting);
+        let keyframes = translations
+            .into_iter()
+            .map(|(at, translation)| {
+                let location = translation * settings.scale;
+                let rotation = rotation_at(&rotations, at).unwrap_or(resting);
+                let mut cam = CamInfo::default();
+                cam.set_location(location.x, location.y, location.z);
+                point_camera_at(&mut cam, (location + rotation * Vec3::NEG_Z).into());
+                CameraKeyframe {
+                    at,
+                    cam,
+                    look_at: None,
+                    easing: Easing::Linear,
+                }
+            })
+            .collect();
+        if out.insert(name.to_owned(), keyframes).is_some() {
+            warn!("more than one node called {name} is animated, using the last one");
+        }
+    }
+    out
+}
+
+fn rotation_at(rotations: &[(f32, Quat)], at: f32) -> Option<Quat> {
+    let i = rotations.iter().position(|(t, _)| *t > at);
+    match i {
+        Some(0) => rotations.first().map(|(_, r)| *r),
+        Some(i) => {
+            let ((t0, r0), (t1, r1)) = (rotations[i - 1], rotations[i]);
+            Some(r0.slerp(r1, (at - t0) / (t1 - t0)))
+        }
+        None => rotations.last().map(|(_, r)| *r),
+    }
+}
@@ -5,17 +5,21 @@ use egui::Context;
 use parking_lot::Mutex;
 use uuid::Uuid;
 
-use crate::theater::{
-    basement::cla::GameProgrammeSettings,
-    play::{orchestra::Orchestra, Definitions, Implementations},
+use crate::{
+    theater::{
+        basement::{cla::GameProgrammeSettings, input_handling::InputContext},
+        play::{orchestra::Orchestra, Definitions, Implementations},
+    },
+    GameProgrammeState,
 };
 
-pub trait Choral<UserData> {
+pub trait Choral<InputContextEnum: InputContext, UserData> {
     fn implement_chorus_for_choral(
         &self,
         egui_ctx: Context,
         orchestra: Arc<Orchestra>,
         settings: &GameProgrammeSettings,
+        state: &mut GameProgrammeState<InputContextEnum>,
         user_data: Arc<Mutex<UserData>>,
     );
     fn chorus_uuid(&self) -> Uuid;
@@ -1 +1,380 @@
+// reading scene files so writers can add scenes and cameras without recompiling
+use std::{collections::HashSet, f32::consts::FRAC_PI_2};
+
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+use crate::theater::play::{
+    backstage::plumbing::asset_loader::{AssetError, AssetLoader, AssetPath},
+    curtain::CurtainDefinition,
+};
+
+use super::{
+    actors::ActressDefinition, interactions::Interaction,
+    stage3d::camera_controllers::CameraController, CamInfo, SceneDefinition,
+};
+
 pub mod linac_lab;
+pub mod transform;
+
+#[derive(Debug, Error)]
+pub enum DefinitionError {
+    #[error(transparent)]
+    Asset(#[from] AssetError),
+    #[error("{path} is not valid UTF-8")]
+    NotUtf8 { path: String },
+    #[error("don't know how to read {path}, scene files have to be .ron or .toml")]
+    UnknownFormat { path: String },
+    #[error("{path}: can't read `{field}`: {message}")]
+    Malformed {
+        path: String,
+        field: String,
+        message: String,
+    },
+    #[error("{path}: `{field}` {problem}")]
+    Invalid {
+        path: String,
+        field: String,
+        problem: String,
+    },
+}
+
+/// A field that parsed fine but doesn't make sense.
+#[derive(Debug)]
+pub struct InvalidField {
+    pub field: String,
+    pub problem: String,
+}
+impl InvalidField {
+    fn new(field: impl Into<String>, problem: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            problem: problem.into(),
+        }
+    }
+}
+
+pub trait Validate {
+    fn validate(&self) -> Result<(), InvalidField>;
+}
+
+impl Validate for SceneDefinition {
+    fn validate(&self) -> Result<(), InvalidField> {
+        let (stage_name, stage_directory) = &self.stage;
+        if stage_name.is_empty() || stage_directory.is_empty() {
+            return Err(InvalidField::new(
+                "stage",
+                "needs both a name and a directory",
+            ));
+        }
+        // the start camera can come with the stage, which isn't loaded yet
+        if self.start_cam.is_empty() {
+            return Err(InvalidField::new("start_cam", "is empty"));
+        }
+        for (name, cam_info) in &self.cameras {
+            for (coordinate, value) in ["x", "y", "z", "pitch", "yaw"]
+                .into_iter()
+                .zip(cam_info.as_arr())
+            {
+                if !value.is_finite() {
+                    return Err(InvalidField::new(
+                        format!("cameras.{name}.{coordinate}"),
+                        "has to be a finite number",
+                    ));
+                }
+            }
+            validate_lens(&format!("cameras.{name}"), cam_info)?;
+        }
+        for (name, path) in &self.camera_paths {
+            if path.keyframes.is_empty() == path.stage_node.is_none() {
+                return Err(InvalidField::new(
+                    format!("camera_paths.{name}"),
+                    "needs either `keyframes` or a `stage_node`, not both",
+                ));
+            }
+            for (i, keyframe) in path.keyframes.iter().enumerate() {
+                let numbers = keyframe.cam.as_arr().into_iter().chain([keyframe.at]);
+                let look_at = keyframe.look_at.into_iter().chain(path.look_at).flatten();
+                if !numbers.chain(look_at).all(f32::is_finite) || keyframe.at < 0.0 {
+                    return Err(InvalidField::new(
+                        format!("camera_paths.{name}.keyframes[{i}]"),
+                        "has to be made of finite numbers, starting at 0 or later",
+                    ));
+                }
+                validate_lens(
+                    &format!("camera_paths.{name}.keyframes[{i}].cam"),
+                    &keyframe.cam,
+                )?;
+            }
+        }
+        match &self.camera_controller {
+            CameraController::Orbit {
+                distance,
+                min_distance,
+                max_distance,
+                ..
+            } if !(0.0 < *min_distance
+                && min_distance <= distance
+                && distance <= max_distance
+                && max_distance.is_finite()) =>
+            {
+                return Err(InvalidField::new(
+                    "camera_controller",
+                    "has to orbit at 0 < min_distance <= distance <= max_distance",
+                ));
+            }
+            CameraController::Walker(character)
+                if !(character.radius > 0.0
+                    && character.height >= 2.0 * character.radius
+                    && 0.0 < character.eye_height
+                    && character.eye_height <= character.height
+                    && character.height.is_finite()) =>
+            {
+                return Err(InvalidField::new(
+                    "camera_controller",
+                    "has to walk with 0 < radius, 2 * radius <= height and 0 < eye_height <= height",
+                ));
+            }
+            CameraController::Walker(character)
+                if !(0.0..character.height).contains(&character.step_height)
+                    || !(0.0..FRAC_PI_2).contains(&character.max_slope)
+                    || !(character.gravity >= 0.0 && character.gravity.is_finite()) =>
+            {
+                return Err(InvalidField::new(
+                    "camera_controller",
+                    "has to walk with a step_height under its height, a max_slope under 90 degrees and gravity that isn't negative",
+                ));
+            }
+            CameraController::LookAt { actress }
+                if !self.actors.iter().any(|a| &a.name == actress) =>
+            {
+                return Err(InvalidField::new(
+                    "camera_controller",
+                    format!("looks at `{actress}` who isn't in `actors`"),
+                ));
+            }
+            _ => {}
+        }
+        validate_actors(&self.actors)?;
+        let mut seen = HashSet::new();
+        for (i, prop) in self.props.iter().enumerate() {
+            if prop.name.is_empty() {
+                return Err(InvalidField::new(format!("props[{i}].name"), "is empty"));
+            }
+            if !seen.insert(prop.name.as_str()) {
+                return Err(InvalidField::new(
+                    format!("props[{i}].name"),
+                    format!("`{}` is already taken by another prop", prop.name),
+                ));
+            }
+            if prop.directory.is_empty() {
+                return Err(InvalidField::new(
+                    format!("props[{i}].directory"),
+                    "is empty",
+                ));
+            }
+            if !prop.transform.is_finite() {
+                return Err(InvalidField::new(
+                    format!("props[{i}].transform"),
+                    "has to be made of finite numbers",
+                ));
+            }
+        }
+        for (name, hover) in &self.hover {
+            if name.is_empty() {
+                return Err(InvalidField::new("hover", "has an empty collider name"));
+            }
+            if hover
+                .highlight
+                .is_some_and(|rgba| !rgba.iter().all(|c| (0.0..=1.0).contains(c)))
+            {
+                return Err(InvalidField::new(
+                    format!("hover.{name}.highlight"),
+                    "has to be made of numbers from 0 to 1",
+                ));
+            }
+        }
+        for (name, interaction) in &self.interactions {
+            if name.is_empty() {
+                return Err(InvalidField::new(
+                    "interactions",
+                    "has an empty collider name",
+                ));
+            }
+            match interaction {
+                Interaction::SwitchCamera { glide, .. }
+                    if !(glide.is_finite() && *glide >= 0.0) =>
+                {
+                    return Err(InvalidField::new(
+                        format!("interactions.{name}.glide"),
+                        "has to be a finite number of seconds, 0 or more",
+                    ));
+                }
+                Interaction::SwitchCamera { camera: target, .. }
+                | Interaction::PlayJingle(target)
+                | Interaction::ChangeScene(target)
+                | Interaction::Callback(target)
+                    if target.is_empty() =>
+                {
+                    return Err(InvalidField::new(
+                        format!("interactions.{name}"),
+                        "doesn't say what to switch to, play or call",
+                    ));
+                }
+                _ => {}
+            }
+        }
+        for (field, names) in [
+            ("colliders", &self.colliders),
+            ("walkable", &self.walkable),
+            ("blocking", &self.blocking),
+        ] {
+            if let Some(i) = names.iter().position(String::is_empty) {
+                return Err(InvalidField::new(
+                    format!("{field}[{i}]"),
+                    "is an empty node name",
+                ));
+            }
+        }
+        let collider_ids = self.collider_ids();
+        if let Some(name) = self
+            .collider_shapes
+            .keys()
+            .find(|name| !collider_ids.contains_key(*name))
+        {
+            return Err(InvalidField::new(
+                format!("collider_shapes.{name}"),
+                "isn't in `colliders`, `walkable` or `blocking`",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Validate for CurtainDefinition {
+    fn validate(&self) -> Result<(), InvalidField> {
+        for (i, (name, directory)) in self.backgrounds.iter().enumerate() {
+            if name.is_empty() || directory.is_empty() {
+                return Err(InvalidField::new(
+                    format!("backgrounds[{i}]"),
+                    "needs both a name and a directory",
+                ));
+            }
+        }
+        validate_actors(&self.actors)
+    }
+}
+
+fn validate_lens(field: &str, cam_info: &CamInfo) -> Result<(), InvalidField> {
+    if !(cam_info.vfov > 0.0 && cam_info.vfov < 180.0) {
+        return Err(InvalidField::new(
+            format!("{field}.vfov"),
+            "has to be between 0 and 180 degrees",
+        ));
+    }
+    if !(cam_info.near > 0.0 && cam_info.near.is_finite()) {
+        return Err(InvalidField::new(
+            format!("{field}.near"),
+            "has to be a positive number",
+        ));
+    }
+    if cam_info
+        .ortho_size
+        .is_some_and(|size| !(size > 0.0 && size.is_finite()))
+    {
+        return Err(InvalidField::new(
+            format!("{field}.ortho_size"),
+            "has to be a positive number",
+        ));
+    }
+    Ok(())
+}
+
+fn validate_actors(actors: &[ActressDefinition]) -> Result<(), InvalidField> {
+    let mut seen = HashSet::new();
+    for (i, actress) in actors.iter().enumerate() {
+        if actress.name.is_empty() {
+            return Err(InvalidField::new(format!("actors[{i}].name"), "is empty"));
+        }
+        if !seen.insert(actress.name.as_str()) {
+            return Err(InvalidField::new(
+                format!("actors[{i}].name"),
+                format!("`{}` is already taken by another actress", actress.name),
+            ));
+        }
+        if actress.directory.is_empty() {
+            return Err(InvalidField::new(
+                format!("actors[{i}].directory"),
+                "is empty",
+            ));
+        }
+        if !(actress.size.is_finite() && actress.size > 0.0) {
+            return Err(InvalidField::new(
+                format!("actors[{i}].size"),
+                "has to be a positive number",
+            ));
+        }
+        if !actress.transform.is_finite() {
+            return Err(InvalidField::new(
+                format!("actors[{i}].transform"),
+                "has to be made of finite numbers",
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn parse_definition<T: DeserializeOwned>(path: &str, text: &str) -> Result<T, DefinitionError> {
+    let malformed = |field: String, message: String| DefinitionError::Malformed {
+        path: path.to_owned(),
+        field,
+        message,
+    };
+    if path.ends_with(".ron") {
+        let mut deserializer = ron::Deserializer::from_str(text)
+            .map_err(|e| malformed(".".to_owned(), e.to_string()))?;
+        let definition = serde_path_to_error::deserialize(&mut deserializer).map_err(|e| {
+            let field = e.path().to_string();
+            malformed(field, deserializer.span_error(e.into_inner()).to_string())
+        })?;
+        deserializer
+            .end()
+            .map_err(|e| malformed(".".to_owned(), deserializer.span_error(e).to_string()))?;
+        Ok(definition)
+    } else if path.ends_with(".toml") {
+        serde_path_to_error::deserialize(toml::Deserializer::new(text)).map_err(|e| {
+            let field = e.path().to_string();
+            malformed(field, e.into_inner().message().to_owned())
+        })
+    } else {
+        Err(DefinitionError::UnknownFormat {
+            path: path.to_owned(),
+        })
+    }
+}
+
+/// Read and check a definition from a `.ron` or `.toml` file relative to the assets base.
+pub fn load_definition<T: DeserializeOwned + Validate>(path: &str) -> Result<T, DefinitionError> {
+    let loader = AssetLoader::default();
+    let bytes = pollster::block_on(loader.get_asset(AssetPath::Internal(path)))?;
+    let text = String::from_utf8(bytes).map_err(|_| DefinitionError::NotUtf8 {
+        path: path.to_owned(),
+    })?;
+    let definition: T = parse_definition(path, &text)?;
+    definition
+        .validate()
+        .map_err(|InvalidField { field, problem }| DefinitionError::Invalid {
+            path: path.to_owned(),
+            field,
+            problem,
+        })?;
+    Ok(definition)
+}
+
+pub fn load_scene_definition(path: &str) -> Result<SceneDefinition, DefinitionError> {
+    load_definition(path)
+}
+
+pub fn load_curtain_definition(path: &str) -> Result<CurtainDefinition, DefinitionError> {
+    load_definition(path)
+}
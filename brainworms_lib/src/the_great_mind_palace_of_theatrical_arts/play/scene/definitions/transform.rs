@@ -0,0 +1,42 @@
+// nobody wants to write a 4x4 matrix by hand in a scene file
+use glam::{EulerRot, Mat4, Quat, Vec3};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// How a transform is written in scene files. `rotation` is XYZ euler angles in radians.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TransformFile {
+    #[serde(default)]
+    translation: Vec3,
+    #[serde(default)]
+    rotation: Vec3,
+    #[serde(default = "unit_scale")]
+    scale: Vec3,
+}
+fn unit_scale() -> Vec3 {
+    Vec3::ONE
+}
+
+pub fn serialize<S: Serializer>(transform: &Mat4, serializer: S) -> Result<S::Ok, S::Error> {
+    let (scale, rotation, translation) = transform.to_scale_rotation_translation();
+    let (x, y, z) = rotation.to_euler(EulerRot::XYZ);
+    TransformFile {
+        translation,
+        rotation: Vec3::new(x, y, z),
+        scale,
+    }
+    .serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Mat4, D::Error> {
+    let TransformFile {
+        translation,
+        rotation,
+        scale,
+    } = TransformFile::deserialize(deserializer)?;
+    Ok(Mat4::from_scale_rotation_translation(
+        scale,
+        Quat::from_euler(EulerRot::XYZ, rotation.x, rotation.y, rotation.z),
+        translation,
+    ))
+}
@@ -0,0 +1,255 @@
+// picking every frame instead of only on click, so whatever the mouse is over can light up and
+// say what it is
+use std::sync::Arc;
+
+use brainworms_arson::egui;
+use glam::{Mat4, Vec3, Vec3A, Vec4};
+use parry3d::{bounding_volume::BoundingVolume, shape::TriMesh};
+use rend3::{
+    types::{Handedness, ObjectHandle},
+    Renderer,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use winit::window::CursorIcon;
+
+use crate::{
+    theater::{
+        basement::input_handling::InputContext,
+        play::{Definitions, Implementations, Playable},
+    },
+    GameProgramme, GameProgrammeState,
+};
+
+use super::stage3d::{
+    compute_projection_matrix, make_ray,
+    picking::{CollisionGroups, PickFilter, PickHit},
+    Colliders,
+};
+
+/// how much bigger than the collider the glow over it is, so it isn't hidden inside it
+const HIGHLIGHT_INFLATION: f32 = 1.02;
+
+/// What happens while the mouse is over one of the scene's colliders, by collider name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HoverDefinition {
+    /// shown next to whatever the mouse is over
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// rgba glow over the collider, none for no glow
+    #[serde(default = "default_highlight")]
+    pub highlight: Option<[f32; 4]>,
+    #[serde(default = "default_cursor")]
+    pub cursor: CursorIcon,
+}
+fn default_highlight() -> Option<[f32; 4]> {
+    Some([1.0, 0.9, 0.4, 0.25])
+}
+fn default_cursor() -> CursorIcon {
+    CursorIcon::Pointer
+}
+
+/// The collider the mouse is over, if the scene has anything to say about it.
+pub struct Hover {
+    pub collider: String,
+    /// where the mouse ray went into it
+    pub point: Vec3A,
+    pub prompt: Option<String>,
+    /// dropping it takes the glow away
+    _highlight: Option<ObjectHandle>,
+}
+
+impl<InputContextEnum: InputContext> GameProgrammeState<InputContextEnum> {
+    /// Forget what the mouse was over, taking the glow and cursor away with it.
+    pub fn clear_hover(&mut self) {
+        if self.hover.take().is_some() {
+            if let Some(window) = &self.window {
+                window.set_cursor_icon(CursorIcon::Default);
+            }
+        }
+    }
+}
+
+impl<
+        PlayablesEnum: Playable<InputContextEnum, UserData> + 'static,
+        InputContextEnum: InputContext + 'static,
+        UserData: Default + Serialize + DeserializeOwned + 'static,
+    > GameProgramme<PlayablesEnum, InputContextEnum, UserData>
+{
+    /// Find what the mouse is over in the current scene. Nothing is while the mouse is grabbed
+    /// or over egui.
+    pub(crate) fn rig_hover(&mut self) {
+        let state = &mut self.state;
+        let mouse_free = !state.grabber.as_ref().is_some_and(|g| g.grabbed())
+            && !state
+                .egui_ctx
+                .as_ref()
+                .is_some_and(|ctx| ctx.is_pointer_over_area());
+        let playable = state
+            .current_playable
+            .and_then(|id| self.data.play.playables.get_mut(&id));
+        let (Some(playable), Some(cur_camera), true) =
+            (playable, state.cur_camera.as_ref(), mouse_free)
+        else {
+            state.clear_hover();
+            return;
+        };
+        let Definitions::SceneDefinition(definition) = playable.playable_definition() else {
+            state.clear_hover();
+            return;
+        };
+        if definition.hover.is_empty() {
+            state.clear_hover();
+            return;
+        }
+        let Some(Implementations::SceneImplementation(implementation)) =
+            playable.playable_implementation()
+        else {
+            state.clear_hover();
+            return;
+        };
+        let (win_w, win_h) = state.input_status.window_size.into();
+        let ray = make_ray(
+            cur_camera,
+            &state.input_status.mouse_physical_poz,
+            win_w,
+            win_h,
+            self.settings.handedness,
+        );
+        let filter = PickFilter {
+            groups: CollisionGroups::PICKABLE,
+            ..Default::default()
+        };
+        // whatever's nearest, props included, hides anything behind it
+        let Some((hit, _)) = implementation.pick(&ray, &filter) else {
+            state.clear_hover();
+            return;
+        };
+        let Definitions::SceneDefinition(definition) = playable.playable_definition() else {
+            return;
+        };
+        let Some(hover_definition) = definition.hover.get(&hit.collider).cloned() else {
+            state.clear_hover();
+            return;
+        };
+
+        let point = Vec3A::new(hit.point.x, hit.point.y, hit.point.z);
+        if let Some(hover) = state
+            .hover
+            .as_mut()
+            .filter(|hover| hover.collider == hit.collider)
+        {
+            hover.point = point;
+            return;
+        }
+        let highlight = match (hover_definition.highlight, &state.renderer) {
+            // picked again for the colliders, only when the mouse gets to something new
+            (Some(color), Some(renderer)) => match playable.playable_implementation() {
+                Some(Implementations::SceneImplementation(implementation)) => implementation
+                    .pick(&ray, &filter)
+                    .and_then(|(hit, colliders)| {
+                        highlight_collider(
+                            renderer,
+                            colliders,
+                            &hit,
+                            color,
+                            self.settings.handedness,
+                        )
+                    }),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(window) = &state.window {
+            window.set_cursor_icon(hover_definition.cursor);
+        }
+        state.hover = Some(Hover {
+            collider: hit.collider,
+            point,
+            prompt: hover_definition.prompt,
+            _highlight: highlight,
+        });
+    }
+}
+
+/// A see-through shell over the collider that was hit, a bit bigger than it.
+fn highlight_collider(
+    renderer: &Arc<Renderer>,
+    colliders: &Colliders,
+    hit: &PickHit,
+    color: [f32; 4],
+    handedness: Handedness,
+) -> Option<ObjectHandle> {
+    let trimeshes: &[TriMesh] = colliders.col_map.get(&hit.collider)?;
+    let center = trimeshes
+        .iter()
+        .map(|trimesh| *trimesh.local_aabb())
+        .reduce(|a, b| a.merged(&b))?
+        .center();
+    let center = Vec3::new(center.x, center.y, center.z);
+    let mut vertices = vec![];
+    let mut indices = vec![];
+    for trimesh in trimeshes {
+        let offset = vertices.len() as u32;
+        vertices.extend(trimesh.vertices().iter().map(|v| Vec3::new(v.x, v.y, v.z)));
+        indices.extend(trimesh.flat_indices().iter().map(|i| i + offset));
+    }
+    let mesh = rend3::types::MeshBuilder::new(vertices, handedness)
+        .with_indices(indices)
+        .build()
+        .ok()?;
+    let material = renderer.add_material(rend3_routine::pbr::PbrMaterial {
+        albedo: rend3_routine::pbr::AlbedoComponent::Value(Vec4::from(color)),
+        transparency: rend3_routine::pbr::Transparency::Blend,
+        unlit: true,
+        ..Default::default()
+    });
+    let object = rend3::types::Object {
+        mesh_kind: rend3::types::ObjectMeshKind::Static(renderer.add_mesh(mesh).ok()?),
+        material,
+        // the collider is already in world space, grow it about its own middle
+        transform: Mat4::from_translation(center)
+            * Mat4::from_scale(Vec3::splat(HIGHLIGHT_INFLATION))
+            * Mat4::from_translation(-center),
+    };
+    Some(renderer.add_object(object))
+}
+
+/// The hovered collider's prompt, next to where the mouse ray went into it.
+pub fn show_hover_prompt<InputContextEnum: InputContext>(
+    egui_ctx: &egui::Context,
+    state: &GameProgrammeState<InputContextEnum>,
+    handedness: Handedness,
+) {
+    let (Some(hover), Some(cur_camera)) = (&state.hover, &state.cur_camera) else {
+        return;
+    };
+    let Some(prompt) = &hover.prompt else {
+        return;
+    };
+    let window_size = state.input_status.window_size;
+    let (width, height) = (
+        window_size.width.max(1) as f32,
+        window_size.height.max(1) as f32,
+    );
+    let camera = cur_camera.renderer_camera;
+    let clip = compute_projection_matrix(camera, handedness, width / height)
+        * camera.view
+        * Vec3::from(hover.point).extend(1.0);
+    if clip.w <= 0.0 {
+        // behind the camera
+        return;
+    }
+    let ndc = clip.truncate() / clip.w;
+    let pixels_per_point = egui_ctx.pixels_per_point();
+    let position = egui::pos2(
+        (ndc.x + 1.0) * 0.5 * width / pixels_per_point,
+        (1.0 - ndc.y) * 0.5 * height / pixels_per_point,
+    );
+    egui::Area::new(egui::Id::new("hover prompt"))
+        .fixed_pos(position + egui::vec2(16.0, 16.0))
+        .interactable(false)
+        .show(egui_ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| ui.label(prompt));
+        });
+}
@@ -0,0 +1,158 @@
+// what clicking on the scene's colliders does, looked up by collider name so playables don't
+// have to match names themselves
+use std::time::Duration;
+
+use brainworms_arson::egui;
+use log::{debug, warn};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    theater::{
+        basement::{easing::Easing, input_handling::InputContext},
+        play::{Definitions, Implementations, Playable},
+    },
+    GameProgramme, GameProgrammeState,
+};
+
+use super::stage3d::{
+    make_ray,
+    picking::{CollisionGroups, PickFilter},
+};
+
+/// What picking one of the scene's colliders does, by collider name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub enum Interaction {
+    /// glide over to one of the scene's cameras, taking `glide` seconds
+    SwitchCamera {
+        camera: String,
+        #[serde(default = "default_glide")]
+        glide: f32,
+    },
+    /// a jingle the playable has registered with the orchestra
+    PlayJingle(String),
+    /// lines shown in a window until they're dismissed
+    ShowDialogue(Vec<String>),
+    /// the playable to replace the current one with
+    ChangeScene(String),
+    /// handed to the playable's [`Cued::on_interaction`](crate::theater::play::Cued::on_interaction)
+    Callback(String),
+}
+fn default_glide() -> f32 {
+    0.7
+}
+
+impl<InputContextEnum: InputContext> GameProgrammeState<InputContextEnum> {
+    /// Pick whatever the mouse is over once this frame's input has been handled, and do what the
+    /// scene's `interactions` say to do about it.
+    pub fn pick(&mut self) {
+        self.pick_requested = true;
+    }
+    pub fn dismiss_dialogue(&mut self) {
+        self.dialogue = None;
+    }
+}
+
+impl<
+        PlayablesEnum: Playable<InputContextEnum, UserData> + 'static,
+        InputContextEnum: InputContext + 'static,
+        UserData: Default + Serialize + DeserializeOwned + 'static,
+    > GameProgramme<PlayablesEnum, InputContextEnum, UserData>
+{
+    /// Answer a `pick` asked for this frame. Nothing gets picked through egui.
+    pub(crate) fn rig_pick(&mut self) {
+        let state = &mut self.state;
+        if !std::mem::take(&mut state.pick_requested)
+            || state
+                .egui_ctx
+                .as_ref()
+                .is_some_and(|ctx| ctx.is_pointer_over_area())
+        {
+            return;
+        }
+        let playable = state
+            .current_playable
+            .and_then(|id| self.data.play.playables.get_mut(&id));
+        let (Some(playable), Some(cur_camera)) = (playable, state.cur_camera.as_ref()) else {
+            return;
+        };
+        let Definitions::SceneDefinition(definition) = playable.playable_definition() else {
+            return;
+        };
+        if definition.interactions.is_empty() {
+            return;
+        }
+        let Some(Implementations::SceneImplementation(implementation)) =
+            playable.playable_implementation()
+        else {
+            return;
+        };
+        let (win_w, win_h) = state.input_status.window_size.into();
+        let ray = make_ray(
+            cur_camera,
+            &state.input_status.mouse_physical_poz,
+            win_w,
+            win_h,
+            self.settings.handedness,
+        );
+        let filter = PickFilter {
+            groups: CollisionGroups::PICKABLE,
+            ..Default::default()
+        };
+        let Some((hit, _)) = implementation.pick(&ray, &filter) else {
+            return;
+        };
+        let Definitions::SceneDefinition(definition) = playable.playable_definition() else {
+            return;
+        };
+        let Some(interaction) = definition.interactions.get(&hit.collider).cloned() else {
+            debug!("nothing to do about {}", hit.collider);
+            return;
+        };
+        debug!("{} picked, {interaction:?}", hit.collider);
+        match interaction {
+            Interaction::SwitchCamera { camera, glide } => {
+                state.move_camera_to(&camera, Duration::from_secs_f32(glide), Easing::EaseInOut)
+            }
+            Interaction::PlayJingle(jingle) => {
+                let playable_id = playable.playable_uuid();
+                let played = state
+                    .orchestra
+                    .as_ref()
+                    .and_then(|orchestra| orchestra.play_jingle(playable_id, &jingle));
+                if played.is_none() {
+                    warn!("{jingle} isn't registered, {} stays quiet", hit.collider);
+                }
+            }
+            Interaction::ShowDialogue(lines) => state.dialogue = Some(lines),
+            Interaction::ChangeScene(scene) => state.replace_playable(&scene),
+            Interaction::Callback(callback) => {
+                playable.playable_on_interaction(state, &callback, &hit.collider)
+            }
+        }
+    }
+}
+
+/// The lines an `Interaction::ShowDialogue` brought up, until they're dismissed.
+pub fn show_dialogue<InputContextEnum: InputContext>(
+    egui_ctx: &egui::Context,
+    state: &mut GameProgrammeState<InputContextEnum>,
+) {
+    let Some(lines) = &state.dialogue else {
+        return;
+    };
+    let mut dismissed = false;
+    egui::Window::new("dialogue")
+        .title_bar(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -32.0))
+        .show(egui_ctx, |ui| {
+            for line in lines {
+                ui.label(line);
+            }
+            dismissed = ui.button("ok").clicked();
+        });
+    if dismissed {
+        state.dismiss_dialogue();
+    }
+}
@@ -1,8 +1,393 @@
-use std::collections::HashMap;
+// things lying around on the stage that can be picked up, moved, hidden and repainted
+use std::{collections::HashMap, num::NonZeroU32, path::Path, sync::Arc};
 
-use rend3::types::ObjectHandle;
+use glam::{Mat4, Vec3};
+use log::{info, warn};
+use nalgebra::{Isometry3, Matrix, Translation3};
+use rend3::{
+    types::{MipmapCount, Texture2DHandle},
+    util::typedefs::SsoString,
+    Renderer,
+};
+use rend3_gltf::{GltfLoadSettings, GltfSceneInstance, ImageKey, LoadedGltfScene};
+use rend3_routine::pbr::{
+    AlbedoComponent, AoMRTextures, MaterialComponent, NormalTexture, NormalTextureYDirection,
+    PbrMaterial, Transparency,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use winit::event_loop::EventLoopProxy;
+
+use crate::{
+    theater::play::backstage::plumbing::asset_loader::{AssetLoader, AssetPath, LoadTicket},
+    MyEvent, MyWinitEvent,
+};
+
+use super::stage3d::{collider_building::ColliderSpec, load_colliders_from_gltf, Colliders};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PropDefinition {
+    pub name: String,
+    pub directory: String,
+    #[serde(with = "super::definitions::transform", default)]
+    pub transform: Mat4,
+}
+
+pub enum AstinkProp {
+    Loading,
+    Loaded((String, Uuid, Prop)),
+}
+
+/// A texture the game renders into that is shown on one of the prop's materials,
+/// copied over by [`Prop::refresh_raw_textures`].
+pub struct RawTexture {
+    pub texture: wgpu::Texture,
+    rend3_handle: Texture2DHandle,
+}
 
 pub struct Prop {
-    pub object: ObjectHandle,
-    pub raw_textures: Option<HashMap<String, wgpu::Texture>>,
+    pub scene: LoadedGltfScene,
+    pub instance: GltfSceneInstance,
+    pub transform: Mat4,
+    pub hidden: bool,
+    /// keyed by material name
+    pub raw_textures: HashMap<String, RawTexture>,
+    /// the glb the prop came from, for rebuilding its materials with something swapped out
+    document: gltf::Document,
+    normal_direction: NormalTextureYDirection,
+    /// in world space, they follow the prop around
+    colliders: Colliders,
+    local_colliders: Colliders,
+    node_transforms: Vec<Mat4>,
+}
+
+impl Prop {
+    /// Colliders to pick the prop with, nothing while it's hidden.
+    pub fn colliders(&self) -> Option<&Colliders> {
+        (!self.hidden).then_some(&self.colliders)
+    }
+
+    pub fn move_to(&mut self, renderer: &Renderer, transform: Mat4) {
+        self.transform = transform;
+        self.colliders = transform_colliders(&self.local_colliders, transform);
+        if !self.hidden {
+            self.place_objects(renderer, transform);
+        }
+    }
+
+    pub fn hide(&mut self, renderer: &Renderer) {
+        self.hidden = true;
+        // rend3 has no visibility flag, so squash it to nothing
+        self.place_objects(renderer, Mat4::from_scale(Vec3::ZERO));
+    }
+
+    pub fn show(&mut self, renderer: &Renderer) {
+        self.hidden = false;
+        self.place_objects(renderer, self.transform);
+    }
+
+    /// Show `texture` on the material called `material_name` instead of whatever it had, keeping
+    /// the rest of the material. `texture` has to be uncompressed and copyable from.
+    pub fn replace_material(
+        &mut self,
+        renderer: &Renderer,
+        material_name: &str,
+        texture: wgpu::Texture,
+    ) {
+        let Some(gltf_material) = self
+            .document
+            .materials()
+            .find(|m| m.name() == Some(material_name))
+        else {
+            warn!("no material called {material_name} to replace");
+            return;
+        };
+        let Some(material) = gltf_material
+            .index()
+            .and_then(|index| self.scene.materials.get(index))
+        else {
+            warn!("{material_name} never got loaded, nothing to replace");
+            return;
+        };
+        if !texture.usage().contains(wgpu::TextureUsages::COPY_SRC) {
+            warn!("{material_name} can't show a texture that can't be copied from");
+            return;
+        }
+        let format = texture.format();
+        let Some(texel_size) = format
+            .block_copy_size(None)
+            .filter(|_| !format.is_compressed())
+        else {
+            warn!("{material_name} can't show a {format:?} texture");
+            return;
+        };
+        let size = texture.size();
+        let rend3_handle = match renderer.add_texture_2d(rend3::types::Texture {
+            label: Some(format!("{material_name} raw texture")),
+            format,
+            size: glam::uvec2(size.width, size.height),
+            mip_count: MipmapCount::Specific(NonZeroU32::new(1).unwrap()),
+            mip_source: rend3::types::MipmapSource::Uploaded,
+            data: vec![0; (size.width * size.height * texel_size) as usize],
+        }) {
+            Ok(handle) => handle,
+            Err(e) => {
+                warn!("{material_name} keeps what it had: {e}");
+                return;
+            }
+        };
+        renderer.update_material(
+            &material.inner,
+            PbrMaterial {
+                albedo: AlbedoComponent::Texture(rend3_handle.clone()),
+                ..loaded_material(&gltf_material, &self.scene, self.normal_direction)
+            },
+        );
+        self.raw_textures.insert(
+            material_name.to_owned(),
+            RawTexture {
+                texture,
+                rend3_handle,
+            },
+        );
+        self.refresh_raw_textures(renderer);
+    }
+
+    /// Copy the raw textures into the ones the materials sample, call after drawing into them.
+    pub fn refresh_raw_textures(&self, renderer: &Renderer) {
+        if self.raw_textures.is_empty() {
+            return;
+        }
+        let dc = renderer.data_core.lock();
+        let mut encoder = renderer
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Prop Raw Texture Encoder"),
+            });
+        for raw in self.raw_textures.values() {
+            let target = &dc
+                .d2_texture_manager
+                .get_internal(raw.rend3_handle.get_raw())
+                .texture;
+            encoder.copy_texture_to_texture(
+                raw.texture.as_image_copy(),
+                target.as_image_copy(),
+                raw.texture.size(),
+            );
+        }
+        renderer.queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    fn place_objects(&self, renderer: &Renderer, transform: Mat4) {
+        for (node, node_transform) in self.instance.nodes.iter().zip(&self.node_transforms) {
+            if let Some(object) = &node.inner.object {
+                renderer.set_object_transform(&object.inner, transform * *node_transform);
+            }
+        }
+    }
+}
+
+/// Everything rend3_gltf made out of `material` but its albedo, rebuilt from the glb since rend3
+/// can't hand it back. Texture transforms and the less common extensions are left out.
+fn loaded_material(
+    material: &gltf::Material<'_>,
+    scene: &LoadedGltfScene,
+    normal_direction: NormalTextureYDirection,
+) -> PbrMaterial {
+    let image = |texture: gltf::Texture<'_>, srgb: bool| {
+        let key = ImageKey {
+            index: texture.source().index(),
+            srgb,
+        };
+        scene.images.get(&key).map(|image| image.inner.clone())
+    };
+    let pbr = material.pbr_metallic_roughness();
+    let metallic_roughness = pbr
+        .metallic_roughness_texture()
+        .and_then(|info| image(info.texture(), false));
+    let occlusion = material.occlusion_texture();
+    let ao_factor = occlusion.as_ref().map(|occlusion| occlusion.strength());
+    // the same image for both is the usual way to pack them
+    let aomr_textures = match occlusion {
+        Some(occlusion)
+            if pbr
+                .metallic_roughness_texture()
+                .map(|info| info.texture().index())
+                == Some(occlusion.texture().index()) =>
+        {
+            AoMRTextures::Combined {
+                texture: metallic_roughness,
+            }
+        }
+        Some(occlusion) => AoMRTextures::SwizzledSplit {
+            ao_texture: image(occlusion.texture(), false),
+            mr_texture: metallic_roughness,
+        },
+        None => AoMRTextures::Combined {
+            texture: metallic_roughness,
+        },
+    };
+    let emissive_factor = Vec3::from(material.emissive_factor());
+    let emissive = match material
+        .emissive_texture()
+        .and_then(|info| image(info.texture(), true))
+    {
+        Some(texture) => MaterialComponent::TextureValue {
+            texture,
+            value: emissive_factor,
+        },
+        None => MaterialComponent::Value(emissive_factor),
+    };
+    PbrMaterial {
+        transparency: match material.alpha_mode() {
+            gltf::material::AlphaMode::Opaque => Transparency::Opaque,
+            gltf::material::AlphaMode::Mask => Transparency::Cutout {
+                cutout: material.alpha_cutoff().unwrap_or(0.5),
+            },
+            gltf::material::AlphaMode::Blend => Transparency::Blend,
+        },
+        normal: match material
+            .normal_texture()
+            .and_then(|normal| image(normal.texture(), false))
+        {
+            Some(texture) => NormalTexture::Tricomponent(texture, normal_direction),
+            None => NormalTexture::None,
+        },
+        aomr_textures,
+        ao_factor,
+        metallic_factor: Some(pbr.metallic_factor()),
+        roughness_factor: Some(pbr.roughness_factor()),
+        emissive,
+        unlit: material.unlit(),
+        ..Default::default()
+    }
+}
+
+impl super::SceneImplementation {
+    pub fn prop_mut(&mut self, name: &str) -> Option<&mut Prop> {
+        self.props.get_mut(name)
+    }
+    /// Dropping the prop drops its objects, which takes it off the stage.
+    pub fn remove_prop(&mut self, name: &str) -> Option<Prop> {
+        self.props.remove(name)
+    }
+}
+
+fn transform_colliders(local_colliders: &Colliders, transform: Mat4) -> Colliders {
+    let (s, r, t) = transform.to_scale_rotation_translation();
+    let isometry = Isometry3::from_parts(Translation3::new(t.x, t.y, t.z), r.into());
+    Colliders::new(
+        local_colliders
+            .col_map
+            .iter()
+            .map(|(name, trimeshes)| {
+                let trimeshes = trimeshes
+                    .iter()
+                    .map(|trimesh| {
+                        let mut trimesh = trimesh.clone().scaled(&Matrix::from(s));
+                        trimesh.transform_vertices(&isometry);
+                        trimesh
+                    })
+                    .collect();
+                (name.clone(), trimeshes)
+            })
+            .collect(),
+        local_colliders.groups().clone(),
+    )
+}
+
+/// where rend3_gltf put each node relative to the prop's origin
+fn node_transforms(instance: &GltfSceneInstance, scale: f32) -> Vec<Mat4> {
+    fn world(instance: &GltfSceneInstance, idx: usize, root: Mat4) -> Mat4 {
+        let node = &instance.nodes[idx].inner;
+        let parent = node
+            .parent
+            .map_or(root, |parent| world(instance, parent, root));
+        parent * node.local_transform
+    }
+    let root = Mat4::from_scale(Vec3::splat(scale));
+    (0..instance.nodes.len())
+        .map(|idx| world(instance, idx, root))
+        .collect()
+}
+
+pub async fn create_prop(
+    PropDefinition {
+        name,
+        directory,
+        transform,
+    }: PropDefinition,
+    renderer: Arc<Renderer>,
+    gltf_settings: GltfLoadSettings,
+    event_loop_proxy: EventLoopProxy<MyEvent>,
+    sc_id: Uuid,
+) {
+    let _ticket = LoadTicket::take();
+    let loader = AssetLoader::default();
+    let path = format!("{}/{}.glb", directory, name);
+    let gltf_data = match loader.get_asset(AssetPath::Internal(&path)).await {
+        Ok(d) => d,
+        Err(e) => {
+            warn!("prop {name} stays in the wings: {e}");
+            return;
+        }
+    };
+    let parent_str = Path::new(&path)
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let io_func = |uri: SsoString| async {
+        if let Some(base64) = rend3_gltf::try_load_base64(&uri) {
+            Ok(base64)
+        } else {
+            let full_uri = parent_str.clone() + "/" + uri.as_str();
+            loader.get_asset(AssetPath::External(&full_uri)).await
+        }
+    };
+    let document = match gltf::Gltf::from_slice_without_validation(&gltf_data) {
+        Ok(file) => file.document,
+        Err(e) => {
+            warn!("prop {name} stays in the wings: {e}");
+            return;
+        }
+    };
+    // every mesh of a prop is solid
+    let collider_ids = document
+        .meshes()
+        .filter_map(|m| m.name().map(|n| (n.to_owned(), ColliderSpec::default())))
+        .collect();
+    let local_colliders =
+        match load_colliders_from_gltf(collider_ids, &gltf_data, io_func, &gltf_settings).await {
+            Ok(c) => c,
+            Err(e) => {
+                warn!("prop {name} stays in the wings: {e}");
+                return;
+            }
+        };
+    let (scene, instance) =
+        match rend3_gltf::load_gltf(&renderer, &gltf_data, &gltf_settings, io_func).await {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                warn!("prop {name} stays in the wings: {e}");
+                return;
+            }
+        };
+    let node_transforms = node_transforms(&instance, gltf_settings.scale);
+    let prop = Prop {
+        colliders: transform_colliders(&local_colliders, transform),
+        scene,
+        instance,
+        transform,
+        hidden: false,
+        raw_textures: HashMap::new(),
+        document,
+        normal_direction: gltf_settings.normal_direction,
+        local_colliders,
+        node_transforms,
+    };
+    prop.place_objects(&renderer, transform);
+    info!("prop {name} is on stage");
+    let _ =
+        event_loop_proxy.send_event(MyWinitEvent::Prop(AstinkProp::Loaded((name, sc_id, prop))));
 }
@@ -9,17 +9,42 @@ use std::{
 };
 pub type ColliderName = String;
 pub type ColliderMap = HashMap<ColliderName, Vec<parry3d::shape::TriMesh>>;
-pub type CollisionMap = HashMap<ColliderName, Vec<nalgebra::OPoint<f32, nalgebra::Const<3>>>>;
 pub struct Colliders {
+    /// don't change it without making new `Colliders`, picking goes by what it was built from
     pub col_map: ColliderMap,
+    groups: HashMap<ColliderName, CollisionGroups>,
+    picking: PickingTree,
+}
+impl Colliders {
+    /// Colliders missing from `groups` are only pickable.
+    pub fn new(col_map: ColliderMap, groups: HashMap<ColliderName, CollisionGroups>) -> Self {
+        let picking = PickingTree::new(&col_map, &groups);
+        Self {
+            col_map,
+            groups,
+            picking,
+        }
+    }
+    pub fn groups(&self) -> &HashMap<ColliderName, CollisionGroups> {
+        &self.groups
+    }
+    /// Everything `ray` goes into that gets past `filter`, nearest first.
+    pub fn cast_ray(&self, ray: &Ray, filter: &PickFilter) -> Vec<PickHit> {
+        self.picking.cast_ray(&self.col_map, ray, filter)
+    }
+}
+/// Cameras that came with the stage glb: named camera nodes, and animated nodes to follow.
+#[derive(Debug, Default, Clone)]
+pub struct StageCameras {
+    pub cameras: HashMap<String, CamInfo>,
+    pub paths: StageCameraPaths,
 }
 use glam::{vec3, vec4, Mat3A, UVec2};
 
-use log::info;
-use nalgebra::{Isometry3, Matrix, Point3, Translation3};
+use log::{debug, info, warn};
 use parking_lot::Mutex;
 
-use parry3d::query::{Ray, RayCast};
+use parry3d::query::Ray;
 use rend3::{
     types::{CameraProjection, Handedness, Texture, TextureFormat},
     util::typedefs::SsoString,
@@ -35,12 +60,25 @@ use std::time;
 use crate::{
     theater::{
         basement::input_handling::InputContext,
-        play::backstage::plumbing::asset_loader::{AssetError, AssetLoader, AssetPath},
+        play::backstage::plumbing::asset_loader::{AssetError, AssetLoader, AssetPath, LoadTicket},
     },
     GameProgrammeState, MyEvent, MyWinitEvent,
 };
 
-use super::{AstinkScene, CamInfo, Camera};
+use super::{
+    camera_paths::{camera_paths_from_gltf, StageCameraPaths},
+    AstinkScene, CamInfo, Camera, LoadedStage3D, SceneDefinition,
+};
+
+pub mod camera_controllers;
+pub mod character_controller;
+pub mod collider_building;
+pub mod picking;
+
+use self::{
+    collider_building::{build_collider_map, ColliderSpec},
+    picking::{CollisionGroups, PickFilter, PickHit, PickingTree},
+};
 
 pub fn lock<T>(lock: &parking_lot::Mutex<T>) -> parking_lot::MutexGuard<'_, T> {
     let guard = lock.lock();
@@ -70,8 +108,9 @@ pub async fn load_stage3d(
     renderer: Arc<Renderer>,
     gltf_settings: GltfLoadSettings,
     event_loop_proxy: EventLoopProxy<MyEvent>,
-    collider_ids: HashMap<String, String>,
+    collider_ids: HashMap<String, ColliderSpec>,
 ) {
+    let _ticket = LoadTicket::take();
     let loader = AssetLoader::default();
     let path = format!("{}/{}.glb", directory, name);
 
@@ -83,11 +122,19 @@ pub async fn load_stage3d(
         collider_ids,
     )
     .await;
-    let _ = event_loop_proxy.send_event(MyWinitEvent::Stage3D(AstinkScene::Loaded((
+    let Some((scene, instance, colliders, cameras)) = ret else {
+        warn!("stage {name} stays in the wings");
+        let _ = event_loop_proxy.send_event(MyWinitEvent::StageMissing(sc_id));
+        return;
+    };
+    let _ = event_loop_proxy.send_event(MyWinitEvent::Stage3D(LoadedStage3D {
         name,
-        sc_id,
-        ret.unwrap(),
-    ))));
+        playable_id: sc_id,
+        scene,
+        instance,
+        colliders,
+        cameras,
+    }));
 }
 
 pub(crate) async fn load_skybox(
@@ -120,8 +167,13 @@ pub(crate) async fn load_gltf(
     loader: &AssetLoader,
     settings: &rend3_gltf::GltfLoadSettings,
     location: AssetPath<'_>,
-    collider_ids: HashMap<String, String>,
-) -> Option<(rend3_gltf::LoadedGltfScene, GltfSceneInstance, Colliders)> {
+    collider_ids: HashMap<String, ColliderSpec>,
+) -> Option<(
+    rend3_gltf::LoadedGltfScene,
+    GltfSceneInstance,
+    Colliders,
+    StageCameras,
+)> {
     // profiling::scope!("loading gltf");
     let gltf_start = time::Instant::now();
     let path = loader.get_asset_path(location);
@@ -136,7 +188,8 @@ pub(crate) async fn load_gltf(
     let gltf_data = match gltf_data_result {
         Ok(d) => d,
         Err(AssetError::FileError { path, error }) => {
-            panic!("Error Loading gltf file {} E: {}", path, error)
+            warn!("couldn't load {path}: {error}");
+            return None;
         }
     };
 
@@ -152,113 +205,128 @@ pub(crate) async fn load_gltf(
         }
     };
     let resources_start = time::Instant::now();
-    let Ok(colliders) = load_colliders_from_gltf(collider_ids, &gltf_data, io_func, settings).await
-    else {
-        panic!("fucked colliders");
+    // colliders, cameras and camera paths all come out of the same document and buffers
+    let (document, buffers) = match read_gltf(&gltf_data, io_func).await {
+        Ok(read) => read,
+        Err(e) => {
+            warn!("couldn't read {path_str}: {e}");
+            return None;
+        }
     };
+    let colliders = colliders_from_gltf::<AssetError>(collider_ids, &document, &buffers, settings)
+        .unwrap_or_else(|e| {
+            warn!("{path_str} doesn't get any colliders: {e}");
+            Colliders::new(HashMap::new(), HashMap::new())
+        });
     info!("built colliders: {:?};", colliders.col_map.keys());
-    let (scene, instance) = rend3_gltf::load_gltf(renderer, &gltf_data, settings, io_func)
-        .await
-        .unwrap();
+    let paths = camera_paths_from_gltf(&document, &buffers, settings);
+    let cameras = cameras_from_gltf(&document, settings);
+    info!("found cameras: {:?};", cameras.keys());
+    let (scene, instance) =
+        match rend3_gltf::load_gltf(renderer, &gltf_data, settings, io_func).await {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                warn!("couldn't load {path_str}: {e}");
+                return None;
+            }
+        };
 
     log::info!(
         "Loaded gltf in {:.3?}, resources loaded in {:.3?}",
         gltf_elapsed,
         resources_start.elapsed()
     );
-    Some((scene, instance, colliders))
+    Some((scene, instance, colliders, StageCameras { cameras, paths }))
 }
 
-pub(crate) async fn load_colliders_from_gltf<F, Fut, E>(
-    collider_ids: HashMap<String, String>,
+/// Colliders for the meshes named in `collider_ids`, in world space, the way `collider_ids` says
+/// to build them.
+pub async fn load_colliders_from_gltf<F, Fut, E>(
+    collider_ids: HashMap<String, ColliderSpec>,
     gltf_data: &[u8],
     io_func: F,
     settings: &GltfLoadSettings,
 ) -> Result<Colliders, GltfLoadError<E>>
+where
+    F: FnMut(SsoString) -> Fut,
+    Fut: Future<Output = Result<Vec<u8>, E>>,
+    E: std::error::Error + 'static,
+{
+    let (document, buffers) = read_gltf(gltf_data, io_func).await?;
+    colliders_from_gltf(collider_ids, &document, &buffers, settings)
+}
+fn colliders_from_gltf<E: std::error::Error + 'static>(
+    collider_ids: HashMap<String, ColliderSpec>,
+    document: &gltf::Document,
+    buffers: &[Vec<u8>],
+    settings: &GltfLoadSettings,
+) -> Result<Colliders, GltfLoadError<E>> {
+    let col_map = build_collider_map(document, buffers, &collider_ids, settings.scale)?;
+    let groups = collider_ids
+        .into_iter()
+        .map(|(name, spec)| (name, spec.groups))
+        .collect();
+    Ok(Colliders::new(col_map, groups))
+}
+/// The glb's document and every buffer it points at.
+pub(crate) async fn read_gltf<F, Fut, E>(
+    gltf_data: &[u8],
+    io_func: F,
+) -> Result<(gltf::Document, Vec<Vec<u8>>), GltfLoadError<E>>
 where
     F: FnMut(SsoString) -> Fut,
     Fut: Future<Output = Result<Vec<u8>, E>>,
     E: std::error::Error + 'static,
 {
     let mut file = gltf::Gltf::from_slice_without_validation(gltf_data)?;
-    let mut out = Colliders {
-        col_map: HashMap::default(),
-    };
     let blob = file.blob.take();
     let buffers = rend3_gltf::load_buffers(file.buffers(), blob, io_func).await?;
-    let nodes: Vec<gltf::Node<'_>> = file.nodes().collect();
+    Ok((file.document, buffers))
+}
+/// Every named camera node in the glb, where it is in the world and what its lens is like.
+pub(crate) fn cameras_from_gltf(
+    document: &gltf::Document,
+    settings: &GltfLoadSettings,
+) -> HashMap<String, CamInfo> {
+    let nodes: Vec<gltf::Node<'_>> = document.nodes().collect();
     let (topological_order, parents) = node_indices_topological_sort(&nodes);
-    let num_nodes = nodes.len();
-
-    debug_assert_eq!(topological_order.len(), num_nodes);
-
-    let node_transforms = vec![glam::Mat4::IDENTITY; num_nodes];
-    let parent_transform = glam::Mat4::from_scale(glam::Vec3::new(
-        settings.scale,
-        settings.scale,
-        settings.scale,
-    ));
-    for node_idx in topological_order.iter() {
-        let node = &nodes[*node_idx];
-
-        let local_transform = glam::Mat4::from_cols_array_2d(&node.transform().matrix());
-
+    let mut node_transforms = vec![glam::Mat4::IDENTITY; nodes.len()];
+    let root_transform = glam::Mat4::from_scale(glam::Vec3::splat(settings.scale));
+    let mut out = HashMap::new();
+    for node_idx in topological_order {
+        let node = &nodes[node_idx];
         let parent_transform = parents
             .get(&node.index())
-            .map(|p| node_transforms[*p])
-            .unwrap_or(parent_transform);
-        let transform = parent_transform * local_transform;
-        //node_transforms[*node_idx] = transform;
-        if let Some(m) = node.mesh() {
-            //    for m in file.meshes() {
-            if m.name()
-                .is_some_and(|n| collider_ids.keys().any(|c| c == &n.to_owned()))
-            {
-                let thename = m.name().unwrap();
-                info!("trying to build collider for {}", &thename);
-                for p in m.primitives() {
-                    if p.mode() != gltf::mesh::Mode::Triangles {
-                        return Err(GltfLoadError::UnsupportedPrimitiveMode(
-                            m.index(),
-                            p.index(),
-                            p.mode(),
-                        ));
-                    }
-                    let reader = p.reader(|b| Some(&buffers[b.index()][..b.length()]));
-
-                    let vertex_positions: Vec<_> = reader
-                        .read_positions()
-                        .ok_or_else(|| GltfLoadError::MissingPositions(m.index()))?
-                        .map(Point3::from)
-                        .collect();
-
-                    if let Some(indices) = reader.read_indices() {
-                        let mut new_trimesh = parry3d::shape::TriMesh::new(
-                            vertex_positions,
-                            indices.into_u32().array_chunks().collect(),
-                        );
-                        //                        let transform = IsometryMatrix3::new();
-                        let (s, r, t) = transform.to_scale_rotation_translation();
-                        let fff = Isometry3::from_parts(Translation3::new(t.x, t.y, t.z), r.into());
-
-                        new_trimesh = new_trimesh.scaled(&Matrix::from(s));
-                        new_trimesh.transform_vertices(&fff);
-
-                        match out.col_map.get_mut(thename) {
-                            Some(oldv) => {
-                                oldv.push(new_trimesh);
-                            }
-                            None => {
-                                out.col_map.insert(thename.to_owned(), vec![new_trimesh]);
-                            }
-                        };
-                    }
-                }
+            .map_or(root_transform, |p| node_transforms[*p]);
+        let transform =
+            parent_transform * glam::Mat4::from_cols_array_2d(&node.transform().matrix());
+        node_transforms[node_idx] = transform;
+        let (Some(camera), Some(name)) = (node.camera(), node.name()) else {
+            continue;
+        };
+        // glTF cameras look down their own -Z
+        let (_, rotation, translation) = transform.to_scale_rotation_translation();
+        let mut cam_info = CamInfo::default();
+        cam_info.set_location(translation.x, translation.y, translation.z);
+        point_camera_at(
+            &mut cam_info,
+            (translation + rotation * glam::Vec3::NEG_Z).into(),
+        );
+        match camera.projection() {
+            gltf::camera::Projection::Perspective(perspective) => {
+                cam_info.vfov = perspective.yfov().to_degrees();
+                cam_info.near = perspective.znear() * settings.scale;
+            }
+            gltf::camera::Projection::Orthographic(orthographic) => {
+                // ymag is half the height
+                cam_info.ortho_size = Some(orthographic.ymag() * 2.0 * settings.scale);
             }
         }
+        out.insert(name.to_owned(), cam_info);
     }
-    Ok(out)
+    out
 }
+
 fn node_indices_topological_sort(nodes: &[gltf::Node]) -> (Vec<usize>, BTreeMap<usize, usize>) {
     // NOTE: The algorithm uses BTreeMaps to guarantee consistent ordering.
 
@@ -293,27 +361,36 @@ fn node_indices_topological_sort(nodes: &[gltf::Node]) -> (Vec<usize>, BTreeMap<
     (topological_sort, parents)
 }
 
-pub fn make_camera(
-    (name, cam_attributes @ [x, y, z, pitch, yaw]): (String, [f32; 5]),
-) -> super::Camera {
-    let camera_location = glam::Vec3A::new(x, y, z);
-    let view = glam::Mat4::from_euler(glam::EulerRot::XYZ, -pitch, -yaw, 0.0);
-    let view = view * glam::Mat4::from_translation((-camera_location).into());
-
+/// Orthographic cameras come out square, [`do_update_camera`] fits them to the window.
+pub fn make_camera((name, info): (String, CamInfo)) -> super::Camera {
     // Set camera location data
     super::Camera {
         name,
         renderer_camera: rend3::types::Camera {
-            projection: rend3::types::CameraProjection::Perspective {
-                vfov: 60.0,
-                near: 0.1,
-            },
-            view,
+            projection: info.projection(1.0),
+            view: camera_view(&info),
         },
-        info: CamInfo::from_arr(&cam_attributes),
+        info,
         rotation: Mat3A::IDENTITY,
     }
 }
+/// The stage's own cameras, with the scene file's over the top of any that have the same name.
+pub fn cast_cameras(definition: &SceneDefinition, stage: &AstinkScene) -> HashMap<String, Camera> {
+    let stage_cameras = match stage {
+        AstinkScene::Loaded((_, _, (_, _, stage_cameras))) => Some(&stage_cameras.cameras),
+        AstinkScene::Loading | AstinkScene::Missing => None,
+    };
+    stage_cameras
+        .into_iter()
+        .flatten()
+        .chain(&definition.cameras)
+        .map(|(name, cam_info)| (name.clone(), make_camera((name.clone(), cam_info.clone()))))
+        .collect()
+}
+fn camera_view(info: &CamInfo) -> glam::Mat4 {
+    let view = glam::Mat4::from_euler(glam::EulerRot::XYZ, -info.pitch, -info.yaw, 0.0);
+    view * glam::Mat4::from_translation((-info.location()).into())
+}
 pub(crate) fn compute_projection_matrix(
     data: rend3::types::Camera,
     handedness: Handedness,
@@ -350,17 +427,17 @@ pub fn make_ray(
     let cam_z = cur_camera.info.z;
     let cam_pitch = cur_camera.info.pitch;
     let cam_yaw = cur_camera.info.yaw;
-    info!("{cam_x},{cam_y},{cam_z},{cam_pitch},{cam_yaw}",);
+    debug!("{cam_x},{cam_y},{cam_z},{cam_pitch},{cam_yaw}",);
     let mouse_x = mouse_physical_poz.x;
     let mouse_y = mouse_physical_poz.y;
-    info!("mouse at {},{}", mouse_x, mouse_y);
+    debug!("mouse at {},{}", mouse_x, mouse_y);
 
     let x = (2.0 * mouse_x) / win_w - 1.0;
 
     let y = 1.0 - (2.0 * mouse_y) / win_h;
     let z = 1.0;
     let ray_nds = vec3(x as f32, y as f32, z as f32);
-    info!("ray_nds: {ray_nds}");
+    debug!("ray_nds: {ray_nds}");
     let ray_clip = vec4(ray_nds.x, ray_nds.y, -1.0, 1.0);
     //        let cur_camera = make_camera(("".to_owned(), [cam_x, cam_y, cam_z, cam_pitch, cam_yaw]));
     let ray_eye = compute_projection_matrix(
@@ -370,51 +447,42 @@ pub fn make_ray(
     )
     .inverse()
         * ray_clip;
+    if let CameraProjection::Orthographic { .. } = cur_camera.renderer_camera.projection {
+        // everything goes straight ahead, from wherever the mouse is on the camera's plane
+        let view_inverse = cur_camera.renderer_camera.view.inverse();
+        let origin = view_inverse * vec4(ray_eye.x, ray_eye.y, 0.0, 1.0);
+        let direction = view_inverse * vec4(0.0, 0.0, -1.0, 0.0);
+        return Ray::new(
+            nalgebra::Point3::new(origin.x, origin.y, origin.z),
+            vec3(direction.x, direction.y, direction.z)
+                .normalize()
+                .into(),
+        );
+    }
     let ray_eye = vec4(ray_eye.x, ray_eye.y, -1.0, 0.0);
-    info!("ray_eye: {ray_eye}");
+    debug!("ray_eye: {ray_eye}");
     let ray_wor4 = cur_camera.renderer_camera.view.inverse() * ray_eye;
     let ray_wor = vec3(ray_wor4.x, ray_wor4.y, ray_wor4.z);
     let ray_wor = ray_wor.normalize();
-    info!("ray_world: {ray_wor}");
+    debug!("ray_world: {ray_wor}");
     Ray::new(nalgebra::Point3::new(cam_x, cam_y, cam_z), ray_wor.into())
 }
-const MAX_TOI: f32 = 100000.0;
+/// Everything under the mouse that gets past `filter`, nearest first.
 pub fn get_collisions_from_camera(
     cur_camera: &Camera,
     mouse_physical_poz: &PhysicalPosition<f64>,
     win_w: f64,
     win_h: f64,
     handedness: Handedness,
-    col_map: &ColliderMap,
-) -> CollisionMap {
-    let cam_point: nalgebra::Point3<f32> = cur_camera.info.location().into();
+    colliders: &Colliders,
+    filter: &PickFilter,
+) -> Vec<PickHit> {
     let rayman = make_ray(cur_camera, mouse_physical_poz, win_w, win_h, handedness);
-    let collisions: CollisionMap = col_map.iter().filter_map(
-        |(c_name, colliders)| -> Option<(String, Vec<nalgebra::OPoint<f32, nalgebra::Const<3>>>)> {
-            let intersections: Vec<_> = colliders
-                .iter()
-                .filter_map(|c| -> Option<nalgebra::OPoint<f32, nalgebra::Const<3>>> {
-                    if let Some(toi) = c.cast_local_ray(&rayman, MAX_TOI, true) {
-                        let intersection = rayman.point_at(toi);
-
-                        if cam_point != intersection {
-                            log::info!("{} intersects mouse ray at {}", c_name, intersection);
-                            Some(intersection)
-                        } else {
-                            None
-                        }
-                    } else {
-                        None
-                    }
-                }).collect();
-            if intersections.is_empty() {
-                None
-            } else {
-                Some((c_name.clone(), intersections))
-            }
-        },
-    ).collect();
-    collisions
+    let hits = colliders.cast_ray(&rayman, filter);
+    for hit in &hits {
+        debug!("{} intersects mouse ray at {}", hit.collider, hit.point);
+    }
+    hits
 }
 #[cfg(feature = "extra_debugging")]
 pub fn draw_line(points: Vec<[f32; 3]>) -> rend3::types::Mesh {
@@ -453,20 +521,12 @@ pub fn draw_line(points: Vec<[f32; 3]>) -> rend3::types::Mesh {
 pub fn do_update_camera<InputContextEnum: InputContext>(
     state: &mut GameProgrammeState<InputContextEnum>,
 ) {
+    let window_size = state.input_status.window_size;
+    let aspect_ratio = window_size.width.max(1) as f32 / window_size.height.max(1) as f32;
     if let Some(cur_camera) = &mut state.cur_camera {
-        let view = glam::Mat4::from_euler(
-            glam::EulerRot::XYZ,
-            -cur_camera.info.pitch,
-            -cur_camera.info.yaw,
-            0.0,
-        );
-        let view = view * glam::Mat4::from_translation((-cur_camera.info.location()).into());
         let renderer_camera = rend3::types::Camera {
-            projection: CameraProjection::Perspective {
-                vfov: 60.0,
-                near: 0.1,
-            },
-            view,
+            projection: cur_camera.info.projection(aspect_ratio),
+            view: camera_view(&cur_camera.info),
         };
         cur_camera.renderer_camera = renderer_camera;
         if let Some(renderer) = &state.renderer {
@@ -474,18 +534,15 @@ pub fn do_update_camera<InputContextEnum: InputContext>(
         }
     }
 }
-pub fn update_camera_mouse_params<InputContextEnum: InputContext>(
+/// Turn the camera by how far the mouse moved. Only meant for while the mouse is grabbed, which
+/// is up to whoever calls it.
+pub fn mouse_look<InputContextEnum: InputContext>(
     absolute_mouse: bool,
+    sensitivity: f32,
     state: &mut GameProgrammeState<InputContextEnum>,
     delta_x: f64,
     delta_y: f64,
 ) {
-    if !state.grabber.as_ref().unwrap().grabbed() {
-        return;
-    }
-
-    const TAU: f32 = std::f32::consts::PI * 2.0;
-
     let mouse_delta = if absolute_mouse {
         let prev = state
             .input_status
@@ -498,20 +555,49 @@ pub fn update_camera_mouse_params<InputContextEnum: InputContext>(
         }
     } else {
         glam::DVec2::new(delta_x, delta_y)
-    };
+    } * sensitivity as f64;
     if let Some(cur_camera) = &mut state.cur_camera {
-        cur_camera.info.yaw -= (mouse_delta.x / 1000.0) as f32;
-        cur_camera.info.pitch -= (mouse_delta.y / 1000.0) as f32;
-        if cur_camera.info.yaw < 0.0 {
-            cur_camera.info.yaw += TAU;
-        } else if cur_camera.info.yaw >= TAU {
-            cur_camera.info.yaw -= TAU;
-        }
-        cur_camera.info.pitch = cur_camera.info.pitch.clamp(
-            -std::f32::consts::FRAC_PI_2 + 0.0001,
-            std::f32::consts::FRAC_PI_2 - 0.0001,
-        )
+        turn_camera(
+            &mut cur_camera.info,
+            -(mouse_delta.x / 1000.0) as f32,
+            -(mouse_delta.y / 1000.0) as f32,
+        );
+    }
+}
+
+/// Turn by `delta_yaw` and `delta_pitch` radians, keeping yaw in a circle and not looking past
+/// straight up or down.
+pub fn turn_camera(info: &mut CamInfo, delta_yaw: f32, delta_pitch: f32) {
+    const TAU: f32 = std::f32::consts::PI * 2.0;
+    info.yaw += delta_yaw;
+    info.pitch += delta_pitch;
+    if info.yaw < 0.0 {
+        info.yaw += TAU;
+    } else if info.yaw >= TAU {
+        info.yaw -= TAU;
     }
+    info.pitch = info.pitch.clamp(
+        -std::f32::consts::FRAC_PI_2 + 0.0001,
+        std::f32::consts::FRAC_PI_2 - 0.0001,
+    )
+}
+
+/// Which way the camera is looking, in the world.
+pub fn camera_forward(info: &CamInfo) -> glam::Vec3A {
+    let (sin_pitch, cos_pitch) = info.pitch.sin_cos();
+    let (sin_yaw, cos_yaw) = info.yaw.sin_cos();
+    glam::Vec3A::new(-cos_pitch * sin_yaw, sin_pitch, -cos_pitch * cos_yaw)
+}
+
+/// Turn to face `target`, leaving the camera where it is. Does nothing when it's already there.
+pub fn point_camera_at(info: &mut CamInfo, target: glam::Vec3A) {
+    let Some(direction) = (target - info.location()).try_normalize() else {
+        return;
+    };
+    info.pitch = direction.y.asin();
+    info.yaw = (-direction.x)
+        .atan2(-direction.z)
+        .rem_euclid(std::f32::consts::TAU);
 }
 
 pub fn update_camera_rotation<InputContextEnum: InputContext>(
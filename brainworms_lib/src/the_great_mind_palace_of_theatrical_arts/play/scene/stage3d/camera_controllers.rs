@@ -0,0 +1,155 @@
+// reusable ways for the camera to get about: round and round something, keeping an eye on an
+// actress, or walking about on foot without going through the scenery
+use glam::{Vec2, Vec3A};
+use parry3d::bounding_volume::BoundingVolume;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    theater::{
+        basement::input_handling::InputContext,
+        play::scene::{CamInfo, SceneDefinition},
+    },
+    GameProgrammeState,
+};
+
+use super::{
+    camera_forward,
+    character_controller::{CharacterController, WalkingColliders},
+    point_camera_at, turn_camera, ColliderMap,
+};
+
+/// how much closer one wheel notch takes an orbiting camera
+const ZOOM_STEP: f32 = 1.1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum OrbitTarget {
+    Point([f32; 3]),
+    /// the middle of a stage collider
+    Collider(String),
+}
+
+/// How the player gets the camera about in a scene, chosen in the scene file.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CameraController {
+    /// the playable moves the camera itself, if at all
+    #[default]
+    Fixed,
+    Orbit {
+        target: OrbitTarget,
+        distance: f32,
+        min_distance: f32,
+        max_distance: f32,
+    },
+    /// stays where it is and keeps an actress in the middle of the picture
+    LookAt { actress: String },
+    /// on foot, against the scene's `walkable` and `blocking` colliders
+    Walker(CharacterController),
+}
+
+/// What the player is asking the controller for this frame, however it was bound.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ControllerInput {
+    /// radians to turn by, yaw then pitch
+    pub look: Vec2,
+    /// right and forward, each in [-1, 1]
+    pub movement: Vec2,
+    /// wheel notches, closer when positive
+    pub zoom: f32,
+    /// world units per second when walking
+    pub speed: f32,
+}
+
+/// The parts of the scene the controllers go by.
+#[derive(Clone, Copy)]
+pub struct ControllerStage<'a> {
+    pub definition: &'a SceneDefinition,
+    /// none until the stage has loaded
+    pub colliders: Option<&'a ColliderMap>,
+}
+
+impl CameraController {
+    /// Move `info` on by a frame `dt` seconds long.
+    pub fn drive(
+        &mut self,
+        info: &mut CamInfo,
+        input: &ControllerInput,
+        dt: f32,
+        stage: ControllerStage,
+    ) {
+        match self {
+            CameraController::Fixed => {}
+            CameraController::Orbit {
+                target,
+                distance,
+                min_distance,
+                max_distance,
+            } => {
+                let Some(target) = orbit_target(target, stage) else {
+                    return;
+                };
+                *distance =
+                    (*distance * ZOOM_STEP.powf(-input.zoom)).clamp(*min_distance, *max_distance);
+                turn_camera(info, input.look.x, input.look.y);
+                let location = target - camera_forward(info) * *distance;
+                info.set_location(location.x, location.y, location.z);
+            }
+            CameraController::LookAt { actress } => {
+                let Some(actress) = stage.definition.actors.iter().find(|a| &a.name == actress)
+                else {
+                    return;
+                };
+                point_camera_at(info, actress.transform.transform_point3a(Vec3A::ZERO));
+            }
+            CameraController::Walker(character) => {
+                turn_camera(info, input.look.x, input.look.y);
+                let forward = camera_forward(info);
+                let forward = Vec3A::new(forward.x, 0.0, forward.z).normalize_or_zero();
+                let right = forward.cross(Vec3A::Y);
+                let movement = input.movement.clamp_length_max(1.0);
+                let step = (right * movement.x + forward * movement.y) * input.speed * dt;
+                let location = match stage.colliders {
+                    Some(col_map) => character.walk(
+                        info.location(),
+                        step,
+                        dt,
+                        &WalkingColliders::of(stage.definition, col_map),
+                    ),
+                    // nothing to stand on yet
+                    None => info.location() + step,
+                };
+                info.set_location(location.x, location.y, location.z);
+            }
+        }
+    }
+}
+
+fn orbit_target(target: &OrbitTarget, stage: ControllerStage) -> Option<Vec3A> {
+    match target {
+        OrbitTarget::Point(point) => Some((*point).into()),
+        OrbitTarget::Collider(name) => {
+            let aabb = stage
+                .colliders?
+                .get(name)?
+                .iter()
+                .map(|trimesh| *trimesh.local_aabb())
+                .reduce(|a, b| a.merged(&b))?;
+            let center = aabb.center();
+            Some(Vec3A::new(center.x, center.y, center.z))
+        }
+    }
+}
+
+impl<InputContextEnum: InputContext> GameProgrammeState<InputContextEnum> {
+    /// Drive the current camera with the scene's controller, unless a camera move or path has
+    /// hold of it.
+    pub fn drive_camera(&mut self, input: &ControllerInput, stage: ControllerStage) {
+        if self.camera_move.is_some() || self.camera_rail.is_some() {
+            return;
+        }
+        let dt = self.frame_delta.as_secs_f32();
+        if let Some(cur_camera) = self.cur_camera.as_mut() {
+            self.camera_controller
+                .drive(&mut cur_camera.info, input, dt, stage);
+        }
+    }
+}
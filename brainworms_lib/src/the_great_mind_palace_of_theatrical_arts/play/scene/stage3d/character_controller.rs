@@ -0,0 +1,230 @@
+// getting about on foot: a capsule that falls, climbs steps and slides along walls instead of
+// going through them
+use glam::{Vec3, Vec3A};
+use nalgebra::{Isometry3, Vector3};
+use parry3d::{
+    query::{time_of_impact, TOIStatus},
+    shape::{Capsule, TriMesh},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::theater::play::scene::SceneDefinition;
+
+use super::ColliderMap;
+
+/// how far from anything the capsule stops, so it doesn't start the next frame touching it
+const SKIN: f32 = 0.01;
+/// how many walls a single step can slide along before giving up
+const MAX_SLIDES: usize = 4;
+/// world units per second, so falling off the stage doesn't get out of hand
+const TERMINAL_SPEED: f32 = 50.0;
+
+/// The stage colliders someone on foot goes by.
+#[derive(Default)]
+pub struct WalkingColliders<'a> {
+    /// can be stood on
+    pub walkable: Vec<&'a TriMesh>,
+    /// get in the way, and get slid off if stood on
+    pub blocking: Vec<&'a TriMesh>,
+}
+impl<'a> WalkingColliders<'a> {
+    /// The scene's `walkable` and `blocking` colliders. A scene that doesn't say can be walked on
+    /// everywhere.
+    pub fn of(definition: &SceneDefinition, col_map: &'a ColliderMap) -> Self {
+        let pick = |names: &[String]| {
+            names
+                .iter()
+                .filter_map(|name| col_map.get(name))
+                .flatten()
+                .collect()
+        };
+        if definition.walkable.is_empty() && definition.blocking.is_empty() {
+            return Self {
+                walkable: col_map.values().flatten().collect(),
+                blocking: vec![],
+            };
+        }
+        Self {
+            walkable: pick(&definition.walkable),
+            blocking: pick(&definition.blocking),
+        }
+    }
+    fn everything(&self) -> impl Iterator<Item = &'a TriMesh> + Clone + '_ {
+        self.walkable.iter().chain(&self.blocking).copied()
+    }
+}
+
+/// A capsule standing in for someone walking about, so the camera can't go through the scenery.
+/// Anything the scene file leaves out is about a person's size.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CharacterController {
+    pub radius: f32,
+    /// top to bottom, round ends included
+    pub height: f32,
+    /// from the bottom of the capsule, where the camera goes
+    pub eye_height: f32,
+    /// how high a ledge can be walked up onto
+    pub step_height: f32,
+    /// radians, anything steeper gets slid down
+    pub max_slope: f32,
+    /// world units per second per second
+    pub gravity: f32,
+    #[serde(skip)]
+    pub fall_speed: f32,
+    #[serde(skip)]
+    pub grounded: bool,
+}
+impl Default for CharacterController {
+    fn default() -> Self {
+        Self {
+            radius: 0.3,
+            height: 1.8,
+            eye_height: 1.7,
+            step_height: 0.3,
+            max_slope: 0.8,
+            gravity: 9.81,
+            fall_speed: 0.0,
+            grounded: false,
+        }
+    }
+}
+
+impl CharacterController {
+    /// Walk the eyes at `eye` by `step` across the floor, falling and climbing as it goes, over
+    /// a frame `dt` seconds long. Returns where the eyes end up.
+    pub fn walk(
+        &mut self,
+        eye: Vec3A,
+        step: Vec3A,
+        dt: f32,
+        colliders: &WalkingColliders,
+    ) -> Vec3A {
+        let step = Vec3A::new(step.x, 0.0, step.z);
+        let mut center = self.center_of(eye);
+        // up over the step first, then across, then back down onto whatever is there
+        let climbed = if self.grounded {
+            let (up, _) = self.slide(center, Vec3A::Y * self.step_height, colliders.everything());
+            up.y - center.y
+        } else {
+            0.0
+        };
+        center.y += climbed;
+        (center, _) = self.slide(center, step, colliders.everything());
+
+        self.fall_speed = (self.fall_speed + self.gravity * dt).min(TERMINAL_SPEED);
+        let fall = self.fall_speed * dt;
+        // stuck to the floor going down slopes and steps, unless falling off something higher
+        let reach = climbed + fall + if self.grounded { self.step_height } else { 0.0 };
+        match self.cast(center, -Vec3A::Y * reach, colliders.everything()) {
+            Some((toi, normal)) => {
+                let standing = normal.y >= self.max_slope.cos()
+                    && self
+                        .cast(
+                            center,
+                            -Vec3A::Y * reach,
+                            colliders.walkable.iter().copied(),
+                        )
+                        .is_some_and(|(walkable_toi, _)| walkable_toi <= toi);
+                if standing {
+                    center.y -= (toi * reach - SKIN).max(0.0);
+                    self.grounded = true;
+                    self.fall_speed = 0.0;
+                } else {
+                    // too steep or not for standing on, down it goes
+                    let (fallen, blocked) =
+                        self.slide(center, -Vec3A::Y * (climbed + fall), colliders.everything());
+                    center = fallen;
+                    if blocked {
+                        // resting on it, no point building up speed
+                        self.fall_speed = 0.0;
+                    }
+                    self.grounded = false;
+                }
+            }
+            None => {
+                center.y -= climbed + fall;
+                self.grounded = false;
+            }
+        }
+        self.eye_of(center)
+    }
+
+    /// Move the eyes at `eye` by `motion` in any direction, no gravity or steps, sliding along
+    /// whatever's in the way. For flying about.
+    pub fn fly(&self, eye: Vec3A, motion: Vec3A, colliders: &WalkingColliders) -> Vec3A {
+        let (center, _) = self.slide(self.center_of(eye), motion, colliders.everything());
+        self.eye_of(center)
+    }
+
+    fn center_of(&self, eye: Vec3A) -> Vec3A {
+        eye - Vec3A::Y * (self.eye_height - self.height / 2.0)
+    }
+    fn eye_of(&self, center: Vec3A) -> Vec3A {
+        center + Vec3A::Y * (self.eye_height - self.height / 2.0)
+    }
+    fn capsule(&self) -> Capsule {
+        Capsule::new_y((self.height / 2.0 - self.radius).max(0.0), self.radius)
+    }
+
+    /// Go as far along `motion` as possible, then along whatever got in the way. Returns where it
+    /// ended up and whether anything did get in the way.
+    fn slide<'a>(
+        &self,
+        mut center: Vec3A,
+        mut motion: Vec3A,
+        colliders: impl Iterator<Item = &'a TriMesh> + Clone,
+    ) -> (Vec3A, bool) {
+        let mut blocked = false;
+        for _ in 0..MAX_SLIDES {
+            let length = motion.length();
+            if length < f32::EPSILON {
+                break;
+            }
+            let Some((toi, normal)) = self.cast(center, motion, colliders.clone()) else {
+                center += motion;
+                break;
+            };
+            blocked = true;
+            center += motion / length * (toi * length - SKIN).max(0.0);
+            let remaining = motion * (1.0 - toi);
+            motion = remaining - normal * remaining.dot(normal);
+        }
+        (center, blocked)
+    }
+
+    /// How far along `motion` the capsule at `center` gets before touching something, as a
+    /// fraction of it, and which way the thing it touched faces.
+    fn cast<'a>(
+        &self,
+        center: Vec3A,
+        motion: Vec3A,
+        colliders: impl Iterator<Item = &'a TriMesh>,
+    ) -> Option<(f32, Vec3A)> {
+        let capsule = self.capsule();
+        let position = Isometry3::translation(center.x, center.y, center.z);
+        let velocity = Vector3::from(Vec3::from(motion));
+        colliders
+            .filter_map(|trimesh| {
+                time_of_impact(
+                    &position,
+                    &velocity,
+                    &capsule,
+                    &Isometry3::identity(),
+                    &Vector3::zeros(),
+                    trimesh,
+                    1.0,
+                    false,
+                )
+                .ok()
+                .flatten()
+            })
+            // already inside something, nothing to go by but getting out of it
+            .filter(|toi| toi.status != TOIStatus::Penetrating)
+            .min_by(|a, b| a.toi.total_cmp(&b.toi))
+            .map(|toi| {
+                let normal = toi.normal2;
+                (toi.toi, Vec3A::new(normal.x, normal.y, normal.z))
+            })
+    }
+}
@@ -0,0 +1,254 @@
+// turning the glb's meshes into colliders: every kind of triangle primitive, indexed or not,
+// skinned meshes in the pose the glb leaves them in, and cheaper stand-ins for colliders that
+// don't need every triangle
+use std::collections::HashMap;
+
+use glam::{Mat4, Vec3};
+use gltf::mesh::Mode;
+use log::{info, warn};
+use nalgebra::Point3;
+use parry3d::{bounding_volume::Aabb, shape::TriMesh, transformation::convex_hull};
+use rend3_gltf::GltfLoadError;
+use serde::{Deserialize, Serialize};
+
+use super::{node_indices_topological_sort, picking::CollisionGroups, ColliderMap, ColliderName};
+
+/// What a collider gets built as.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColliderShape {
+    /// every triangle of the mesh
+    #[default]
+    Mesh,
+    /// the smallest convex shape around the mesh
+    ConvexHull,
+    /// a box around the mesh, lined up with the node it's on
+    Box,
+}
+
+/// What to build for a mesh and what it's for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ColliderSpec {
+    pub groups: CollisionGroups,
+    pub shape: ColliderShape,
+}
+
+/// A trimesh in world space for every node with a mesh named in `specs`, each mesh's primitives
+/// together. Animations and morph targets aren't played, skinned meshes are posed the way the glb
+/// leaves their joints.
+pub(crate) fn build_collider_map<E: std::error::Error + 'static>(
+    document: &gltf::Document,
+    buffers: &[Vec<u8>],
+    specs: &HashMap<ColliderName, ColliderSpec>,
+    scale: f32,
+) -> Result<ColliderMap, GltfLoadError<E>> {
+    let nodes: Vec<gltf::Node<'_>> = document.nodes().collect();
+    let (topological_order, parents) = node_indices_topological_sort(&nodes);
+    debug_assert_eq!(topological_order.len(), nodes.len());
+    let root_transform = Mat4::from_scale(Vec3::splat(scale));
+    // every node first, skins want their joints wherever they are in the tree
+    let mut node_transforms = vec![Mat4::IDENTITY; nodes.len()];
+    for &node_idx in &topological_order {
+        let parent_transform = parents
+            .get(&node_idx)
+            .map_or(root_transform, |p| node_transforms[*p]);
+        node_transforms[node_idx] =
+            parent_transform * Mat4::from_cols_array_2d(&nodes[node_idx].transform().matrix());
+    }
+    let read_buffer = |b: gltf::Buffer<'_>| Some(&buffers[b.index()][..b.length()]);
+
+    let mut col_map = ColliderMap::default();
+    for &node_idx in &topological_order {
+        let node = &nodes[node_idx];
+        let Some(mesh) = node.mesh() else {
+            continue;
+        };
+        let Some((name, spec)) = mesh.name().and_then(|n| specs.get_key_value(n)) else {
+            continue;
+        };
+        info!("trying to build collider for {name}");
+        // skinned vertices get put in the world by their joints, the node's own transform
+        // doesn't count
+        let joint_matrices = node.skin().map(|skin| {
+            let inverse_binds: Vec<Mat4> = skin
+                .reader(read_buffer)
+                .read_inverse_bind_matrices()
+                .map(|matrices| matrices.map(|m| Mat4::from_cols_array_2d(&m)).collect())
+                .unwrap_or_default();
+            skin.joints()
+                .enumerate()
+                .map(|(i, joint)| {
+                    node_transforms[joint.index()]
+                        * inverse_binds.get(i).copied().unwrap_or(Mat4::IDENTITY)
+                })
+                .collect::<Vec<_>>()
+        });
+        let mesh_to_world = match joint_matrices {
+            Some(_) => Mat4::IDENTITY,
+            None => node_transforms[node_idx],
+        };
+
+        let mut points: Vec<Vec3> = vec![];
+        let mut triangles: Vec<[u32; 3]> = vec![];
+        for primitive in mesh.primitives() {
+            let reader = primitive.reader(read_buffer);
+            let mut positions: Vec<Vec3> = reader
+                .read_positions()
+                .ok_or_else(|| GltfLoadError::MissingPositions(mesh.index()))?
+                .map(Vec3::from)
+                .collect();
+            if let Some(joint_matrices) = &joint_matrices {
+                match (reader.read_joints(0), reader.read_weights(0)) {
+                    (Some(joints), Some(weights)) => {
+                        for ((position, joints), weights) in positions
+                            .iter_mut()
+                            .zip(joints.into_u16())
+                            .zip(weights.into_f32())
+                        {
+                            *position = skin_position(*position, joints, weights, joint_matrices);
+                        }
+                    }
+                    _ => {
+                        warn!("{name} has a skin but no joints or weights, leaving it on its node");
+                        let node_transform = node_transforms[node_idx];
+                        for position in &mut positions {
+                            *position = node_transform.transform_point3(*position);
+                        }
+                    }
+                }
+            }
+            // no indices means every vertex once, in order
+            let indices: Vec<u32> = match reader.read_indices() {
+                Some(indices) => indices.into_u32().collect(),
+                None => (0..positions.len() as u32).collect(),
+            };
+            let Some(primitive_triangles) = triangles_of(primitive.mode(), &indices) else {
+                warn!(
+                    "{name} primitive {} is {:?}, no triangles there to collide with",
+                    primitive.index(),
+                    primitive.mode()
+                );
+                continue;
+            };
+            let offset = points.len() as u32;
+            let vertex_count = positions.len() as u32;
+            triangles.extend(
+                primitive_triangles
+                    .into_iter()
+                    .filter(|triangle| triangle.iter().all(|i| *i < vertex_count))
+                    .map(|triangle| triangle.map(|i| i + offset)),
+            );
+            points.extend(positions);
+        }
+        if triangles.is_empty() {
+            warn!("{name} has no triangles, it doesn't get a collider");
+            continue;
+        }
+        let Some(trimesh) = shape_trimesh(spec.shape, &points, triangles, mesh_to_world) else {
+            warn!(
+                "{name} is too flat for a {:?}, it doesn't get a collider",
+                spec.shape
+            );
+            continue;
+        };
+        col_map.entry(name.clone()).or_default().push(trimesh);
+    }
+    Ok(col_map)
+}
+
+/// The triangles `mode` makes out of `indices`, all wound the same way, leaving out any that are
+/// only a line or a point. Nothing for points and lines.
+fn triangles_of(mode: Mode, indices: &[u32]) -> Option<Vec<[u32; 3]>> {
+    let triangles: Vec<[u32; 3]> = match mode {
+        Mode::Triangles => indices
+            .chunks_exact(3)
+            .map(|t| [t[0], t[1], t[2]])
+            .collect(),
+        // every other triangle of a strip goes round the other way, so turn it back
+        Mode::TriangleStrip => indices
+            .windows(3)
+            .enumerate()
+            .map(|(i, t)| match i % 2 {
+                0 => [t[0], t[1], t[2]],
+                _ => [t[0], t[2], t[1]],
+            })
+            .collect(),
+        Mode::TriangleFan => match indices.split_first() {
+            Some((&hub, rim)) => rim.windows(2).map(|t| [t[0], t[1], hub]).collect(),
+            None => vec![],
+        },
+        Mode::Points | Mode::Lines | Mode::LineLoop | Mode::LineStrip => return None,
+    };
+    // strips get stitched together with triangles like these
+    Some(
+        triangles
+            .into_iter()
+            .filter(|[a, b, c]| a != b && b != c && a != c)
+            .collect(),
+    )
+}
+
+/// Where the joints put `position`, weighing each joint by how much it pulls on it.
+fn skin_position(
+    position: Vec3,
+    joints: [u16; 4],
+    weights: [f32; 4],
+    joint_matrices: &[Mat4],
+) -> Vec3 {
+    let total: f32 = weights.iter().sum();
+    if total <= 0.0 {
+        return position;
+    }
+    joints
+        .into_iter()
+        .zip(weights)
+        .filter_map(|(joint, weight)| {
+            let joint_matrix = joint_matrices.get(joint as usize)?;
+            Some(joint_matrix.transform_point3(position) * weight / total)
+        })
+        .sum()
+}
+
+/// `points` and `triangles` made into `shape`, in the world. Nothing when there's no hull to be
+/// made out of them.
+fn shape_trimesh(
+    shape: ColliderShape,
+    points: &[Vec3],
+    triangles: Vec<[u32; 3]>,
+    mesh_to_world: Mat4,
+) -> Option<TriMesh> {
+    let to_world = |p: Vec3| {
+        let p = mesh_to_world.transform_point3(p);
+        Point3::new(p.x, p.y, p.z)
+    };
+    // mirrored nodes turn triangles inside out
+    let mirrored = mesh_to_world.determinant() < 0.0;
+    let unmirror = |mut triangle: [u32; 3]| {
+        if mirrored {
+            triangle.swap(1, 2);
+        }
+        triangle
+    };
+    match shape {
+        ColliderShape::Mesh => Some(TriMesh::new(
+            points.iter().copied().map(to_world).collect(),
+            triangles.into_iter().map(unmirror).collect(),
+        )),
+        ColliderShape::ConvexHull => {
+            let world_points: Vec<_> = points.iter().copied().map(to_world).collect();
+            let (vertices, hull_triangles) = convex_hull(&world_points);
+            (!hull_triangles.is_empty()).then(|| TriMesh::new(vertices, hull_triangles))
+        }
+        ColliderShape::Box => {
+            // around the mesh as it is on its node, then put in the world with the node
+            let local_points: Vec<_> = points.iter().map(|p| Point3::new(p.x, p.y, p.z)).collect();
+            let (corners, box_triangles) = Aabb::from_points(&local_points).to_trimesh();
+            Some(TriMesh::new(
+                corners
+                    .into_iter()
+                    .map(|p| to_world(Vec3::new(p.x, p.y, p.z)))
+                    .collect(),
+                box_triangles.into_iter().map(unmirror).collect(),
+            ))
+        }
+    }
+}
@@ -0,0 +1,129 @@
+// finding what the mouse is over without asking every collider on the stage: a Qbvh over all of
+// them, built once when they're loaded
+use std::{collections::HashMap, ops::BitOr};
+
+use parry3d::{
+    partitioning::Qbvh,
+    query::{visitors::RayIntersectionsVisitor, Ray, RayCast},
+};
+
+use super::{ColliderMap, ColliderName};
+
+/// Which kinds of thing a collider is, for picking only some of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CollisionGroups(pub u32);
+impl CollisionGroups {
+    pub const NONE: Self = Self(0);
+    /// can be clicked on
+    pub const PICKABLE: Self = Self(1);
+    /// can be stood on
+    pub const WALKABLE: Self = Self(1 << 1);
+    /// gets in the way of walking
+    pub const BLOCKING: Self = Self(1 << 2);
+    pub const ALL: Self = Self(u32::MAX);
+
+    pub fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+impl Default for CollisionGroups {
+    fn default() -> Self {
+        Self::PICKABLE
+    }
+}
+impl BitOr for CollisionGroups {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// What to leave out of a pick.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PickFilter {
+    /// along the ray, so world units for the normalized rays from `make_ray`
+    pub max_distance: f32,
+    /// only colliders in at least one of these
+    pub groups: CollisionGroups,
+}
+impl Default for PickFilter {
+    fn default() -> Self {
+        Self {
+            max_distance: MAX_TOI,
+            groups: CollisionGroups::ALL,
+        }
+    }
+}
+const MAX_TOI: f32 = 100000.0;
+
+/// Somewhere a ray went into a collider.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PickHit {
+    pub collider: ColliderName,
+    /// which of the collider's trimeshes, same order as in the `ColliderMap`
+    pub part: usize,
+    pub distance: f32,
+    pub point: nalgebra::Point3<f32>,
+}
+
+/// A Qbvh over every trimesh in a `ColliderMap`, with the groups each collider is in.
+#[derive(Clone)]
+pub struct PickingTree {
+    qbvh: Qbvh<u32>,
+    /// what each leaf of the qbvh is
+    leaves: Vec<(ColliderName, usize, CollisionGroups)>,
+}
+
+impl PickingTree {
+    /// Colliders missing from `groups` are only pickable.
+    pub fn new(col_map: &ColliderMap, groups: &HashMap<ColliderName, CollisionGroups>) -> Self {
+        let leaves: Vec<_> = col_map
+            .iter()
+            .flat_map(|(name, trimeshes)| {
+                let groups = groups.get(name).copied().unwrap_or_default();
+                (0..trimeshes.len()).map(move |part| (name.clone(), part, groups))
+            })
+            .collect();
+        let mut qbvh = Qbvh::new();
+        qbvh.clear_and_rebuild(
+            leaves
+                .iter()
+                .enumerate()
+                .map(|(i, (name, part, _))| (i as u32, *col_map[name][*part].local_aabb())),
+            0.0,
+        );
+        Self { qbvh, leaves }
+    }
+
+    /// Everything `ray` goes into that gets past `filter`, nearest first. The colliders are
+    /// already in world space, so this wants the same `col_map` the tree was built from.
+    pub fn cast_ray(&self, col_map: &ColliderMap, ray: &Ray, filter: &PickFilter) -> Vec<PickHit> {
+        let mut hits = vec![];
+        let mut visit = |leaf: &u32| {
+            let (name, part, groups) = &self.leaves[*leaf as usize];
+            if !groups.intersects(filter.groups) {
+                return true;
+            }
+            let Some(trimesh) = col_map.get(name).and_then(|t| t.get(*part)) else {
+                return true;
+            };
+            // a ray starting inside something hits it straight away, which is no use for picking
+            if let Some(toi) = trimesh
+                .cast_local_ray(ray, filter.max_distance, true)
+                .filter(|toi| *toi > 0.0)
+            {
+                hits.push(PickHit {
+                    collider: name.clone(),
+                    part: *part,
+                    distance: toi,
+                    point: ray.point_at(toi),
+                });
+            }
+            true
+        };
+        let mut visitor = RayIntersectionsVisitor::new(ray, filter.max_distance, &mut visit);
+        self.qbvh.traverse_depth_first(&mut visitor);
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits
+    }
+}
@@ -0,0 +1,274 @@
+// colliders built from tiny glbs made up on the spot, one for each way a mesh can be put together
+use std::collections::HashMap;
+
+use brainworms_lib::{
+    glam::{Mat4, Quat, Vec3},
+    rend3::util::typedefs::SsoString,
+    theater::play::scene::stage3d::{
+        collider_building::{ColliderShape, ColliderSpec},
+        load_colliders_from_gltf, ColliderMap,
+    },
+};
+
+const LINES: u32 = 1;
+const TRIANGLES: u32 = 4;
+const TRIANGLE_STRIP: u32 = 5;
+const TRIANGLE_FAN: u32 = 6;
+
+const QUAD: [[f32; 3]; 4] = [
+    [0.0, 0.0, 0.0],
+    [1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0],
+    [1.0, 0.0, 1.0],
+];
+
+/// The one buffer of a glb, with a view and an accessor for everything in it.
+#[derive(Default)]
+struct Bin {
+    data: Vec<u8>,
+    views: Vec<String>,
+    accessors: Vec<String>,
+}
+impl Bin {
+    /// Returns the accessor's index.
+    fn accessor(&mut self, bytes: &[u8], component_type: u32, count: usize, kind: &str) -> usize {
+        let offset = self.data.len();
+        self.data.extend(bytes);
+        self.data.resize(self.data.len().next_multiple_of(4), 0);
+        self.views.push(format!(
+            r#"{{"buffer": 0, "byteOffset": {offset}, "byteLength": {}}}"#,
+            bytes.len()
+        ));
+        self.accessors.push(format!(
+            r#"{{"bufferView": {}, "componentType": {component_type}, "count": {count}, "type": "{kind}"}}"#,
+            self.views.len() - 1
+        ));
+        self.accessors.len() - 1
+    }
+    fn floats(&mut self, values: &[f32], count: usize, kind: &str) -> usize {
+        let bytes: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+        self.accessor(&bytes, 5126, count, kind)
+    }
+    fn positions(&mut self, positions: &[[f32; 3]]) -> usize {
+        self.floats(&positions.concat(), positions.len(), "VEC3")
+    }
+    fn indices(&mut self, indices: &[u16]) -> usize {
+        let bytes: Vec<u8> = indices.iter().flat_map(|i| i.to_le_bytes()).collect();
+        self.accessor(&bytes, 5123, indices.len(), "SCALAR")
+    }
+    /// A glb with `rest` of the json after the buffer, views and accessors.
+    fn glb(self, rest: &str) -> Vec<u8> {
+        let json = format!(
+            r#"{{"asset": {{"version": "2.0"}}, "buffers": [{{"byteLength": {}}}],
+            "bufferViews": [{}], "accessors": [{}], {rest}}}"#,
+            self.data.len(),
+            self.views.join(", "),
+            self.accessors.join(", "),
+        );
+        let chunk = |kind: &[u8; 4], data: &[u8], pad: u8| {
+            let mut data = data.to_vec();
+            data.resize(data.len().next_multiple_of(4), pad);
+            let mut out = (data.len() as u32).to_le_bytes().to_vec();
+            out.extend(kind);
+            out.extend(data);
+            out
+        };
+        let mut chunks = chunk(b"JSON", json.as_bytes(), b' ');
+        chunks.extend(chunk(b"BIN\0", &self.data, 0));
+        let mut out = b"glTF".to_vec();
+        out.extend(2u32.to_le_bytes());
+        out.extend((12 + chunks.len() as u32).to_le_bytes());
+        out.extend(chunks);
+        out
+    }
+}
+
+/// A mesh called "thing" with one primitive, on the first of `nodes`.
+fn thing(mode: u32, positions: &[[f32; 3]], indices: Option<&[u16]>, nodes: &str) -> Vec<u8> {
+    let mut bin = Bin::default();
+    let position = bin.positions(positions);
+    let indices = indices
+        .map(|indices| format!(r#", "indices": {}"#, bin.indices(indices)))
+        .unwrap_or_default();
+    bin.glb(&format!(
+        r#""meshes": [{{"name": "thing", "primitives": [
+            {{"attributes": {{"POSITION": {position}}}{indices}, "mode": {mode}}}
+        ]}}],
+        "nodes": [{nodes}], "scenes": [{{"nodes": [0]}}]"#
+    ))
+}
+
+fn build(gltf_data: &[u8], shape: ColliderShape) -> ColliderMap {
+    let collider_ids = HashMap::from([(
+        "thing".to_owned(),
+        ColliderSpec {
+            shape,
+            ..Default::default()
+        },
+    )]);
+    let io_func = |uri: SsoString| async move {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("everything is in the glb, not {uri}"),
+        ))
+    };
+    pollster::block_on(load_colliders_from_gltf(
+        collider_ids,
+        gltf_data,
+        io_func,
+        &Default::default(),
+    ))
+    .unwrap()
+    .col_map
+}
+
+fn vertices(col_map: &ColliderMap) -> Vec<Vec3> {
+    col_map["thing"]
+        .iter()
+        .flat_map(|trimesh| trimesh.vertices())
+        .map(|v| Vec3::new(v.x, v.y, v.z))
+        .collect()
+}
+
+fn triangle_count(col_map: &ColliderMap) -> usize {
+    col_map["thing"].iter().map(|t| t.indices().len()).sum()
+}
+
+fn assert_close(a: Vec3, b: Vec3) {
+    assert!(a.abs_diff_eq(b, 1e-4), "{a} isn't {b}");
+}
+
+#[test]
+fn children_are_where_their_parents_put_them() {
+    let nodes = r#"
+        {"children": [1], "translation": [0, 10, 0], "scale": [2, 2, 2]},
+        {"mesh": 0, "translation": [1, 0, 0]}
+    "#;
+    let col_map = build(
+        &thing(TRIANGLES, &QUAD[..3], None, nodes),
+        ColliderShape::Mesh,
+    );
+    let vertices = vertices(&col_map);
+    assert_close(vertices[0], Vec3::new(2.0, 10.0, 0.0));
+    assert_close(vertices[1], Vec3::new(4.0, 10.0, 0.0));
+    assert_close(vertices[2], Vec3::new(2.0, 10.0, 2.0));
+}
+
+#[test]
+fn strips_and_fans_become_triangles_facing_the_same_way() {
+    let nodes = r#"{"mesh": 0}"#;
+    let strip = build(
+        &thing(TRIANGLE_STRIP, &QUAD, None, nodes),
+        ColliderShape::Mesh,
+    );
+    // the ones stitching strips together are only lines, so they're left out
+    let stitched = build(
+        &thing(TRIANGLE_STRIP, &QUAD, Some(&[0, 1, 2, 3, 3, 3]), nodes),
+        ColliderShape::Mesh,
+    );
+    let fan = build(
+        &thing(TRIANGLE_FAN, &QUAD, Some(&[0, 1, 3, 2]), nodes),
+        ColliderShape::Mesh,
+    );
+    for col_map in [&strip, &stitched, &fan] {
+        assert_eq!(triangle_count(col_map), 2);
+    }
+    let normals: Vec<f32> = [&strip, &fan]
+        .into_iter()
+        .flat_map(|col_map| col_map["thing"][0].triangles())
+        .map(|triangle| triangle.normal().unwrap().y)
+        .collect();
+    assert!(normals.iter().all(|y| y * normals[0] > 0.0));
+}
+
+#[test]
+fn meshes_without_indices_use_every_vertex_in_order() {
+    let positions = [QUAD[0], QUAD[1], QUAD[2], QUAD[1], QUAD[3], QUAD[2]];
+    let col_map = build(
+        &thing(TRIANGLES, &positions, None, r#"{"mesh": 0}"#),
+        ColliderShape::Mesh,
+    );
+    assert_eq!(triangle_count(&col_map), 2);
+}
+
+#[test]
+fn lines_are_left_out_instead_of_failing() {
+    let col_map = build(
+        &thing(LINES, &QUAD, None, r#"{"mesh": 0}"#),
+        ColliderShape::Mesh,
+    );
+    assert!(!col_map.contains_key("thing"));
+}
+
+#[test]
+fn boxes_turn_with_their_node() {
+    let rotation = Quat::from_rotation_y(std::f32::consts::FRAC_PI_4);
+    let nodes = format!(
+        r#"{{"mesh": 0, "rotation": [{}, {}, {}, {}], "translation": [0, 1, 0]}}"#,
+        rotation.x, rotation.y, rotation.z, rotation.w
+    );
+    let tetrahedron = [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, 1.0],
+    ];
+    let indices = [0, 2, 1, 0, 1, 3, 0, 3, 2, 1, 2, 3];
+    let col_map = build(
+        &thing(TRIANGLES, &tetrahedron, Some(&indices), &nodes),
+        ColliderShape::Box,
+    );
+    assert_eq!(triangle_count(&col_map), 12);
+    let vertices = vertices(&col_map);
+    assert_eq!(vertices.len(), 8);
+    let to_world = Mat4::from_rotation_translation(rotation, Vec3::Y);
+    for corner in [Vec3::ZERO, Vec3::ONE, Vec3::new(1.0, 0.0, 1.0)] {
+        let corner = to_world.transform_point3(corner);
+        assert!(
+            vertices.iter().any(|v| v.abs_diff_eq(corner, 1e-4)),
+            "no corner at {corner}"
+        );
+    }
+}
+
+#[test]
+fn hulls_go_round_the_dents() {
+    // a cube's corners, and one in the middle that the hull leaves out
+    let mut points: Vec<[f32; 3]> = (0..8)
+        .map(|i| [(i & 1) as f32, (i >> 1 & 1) as f32, (i >> 2 & 1) as f32])
+        .collect();
+    points.push([0.5; 3]);
+    let col_map = build(
+        &thing(TRIANGLE_FAN, &points, None, r#"{"mesh": 0}"#),
+        ColliderShape::ConvexHull,
+    );
+    let vertices = vertices(&col_map);
+    assert_eq!(vertices.len(), 8);
+    assert!(!vertices.contains(&Vec3::splat(0.5)));
+}
+
+#[test]
+fn skinned_meshes_go_where_their_joints_are() {
+    let mut bin = Bin::default();
+    let position = bin.positions(&QUAD[..3]);
+    let joints = bin.accessor(&[0; 12], 5121, 3, "VEC4");
+    let weights = bin.floats(&[1.0, 0.0, 0.0, 0.0].repeat(3), 3, "VEC4");
+    // the mesh node's own translation doesn't count once there's a skin
+    let gltf_data = bin.glb(&format!(
+        r#""meshes": [{{"name": "thing", "primitives": [{{"attributes": {{
+            "POSITION": {position}, "JOINTS_0": {joints}, "WEIGHTS_0": {weights}
+        }}}}]}}],
+        "skins": [{{"joints": [2]}}],
+        "nodes": [
+            {{"children": [1, 2]}},
+            {{"mesh": 0, "skin": 0, "translation": [100, 0, 0]}},
+            {{"translation": [0, 5, 0]}}
+        ],
+        "scenes": [{{"nodes": [0]}}]"#
+    ));
+    let col_map = build(&gltf_data, ColliderShape::Mesh);
+    let vertices = vertices(&col_map);
+    assert_close(vertices[0], Vec3::new(0.0, 5.0, 0.0));
+    assert_close(vertices[1], Vec3::new(1.0, 5.0, 0.0));
+    assert_close(vertices[2], Vec3::new(0.0, 5.0, 1.0));
+}
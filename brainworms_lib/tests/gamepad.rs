@@ -0,0 +1,129 @@
+// a simulated pad fed through the same polling the real ones go through
+use std::collections::BTreeMap;
+
+use brainworms_lib::{
+    gilrs::{Axis, Button},
+    glam::Vec2,
+    serde::{Deserialize, Serialize},
+    theater::basement::input_handling::{
+        gamepad::{poll_gamepad, AxisDirection, SimulatedPad},
+        AcceptedInput, InputContext,
+    },
+    GameProgrammeState,
+};
+
+const DEADZONE: f32 = 0.2;
+
+#[derive(Default, Hash, Eq, PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(crate = "brainworms_lib::serde")]
+enum PadIC {
+    Jump,
+    Throttle,
+    #[default]
+    Marker,
+}
+impl InputContext for PadIC {}
+
+/// Plug `pad` in, take everything it has queued and start the next frame.
+fn poll(state: &mut GameProgrammeState<PadIC>, pad: SimulatedPad) {
+    state.input_status.end_frame();
+    state.gamepad = Some(Box::new(pad));
+    poll_gamepad(state, DEADZONE);
+}
+
+fn tilted(tilts: &[(Axis, f32)]) -> SimulatedPad {
+    let mut pad = SimulatedPad::default();
+    for (axis, value) in tilts {
+        pad.tilt(*axis, *value);
+    }
+    pad
+}
+
+#[test]
+fn the_deadzone_reads_nothing_and_the_rest_still_reaches_the_edge() {
+    let mut state = GameProgrammeState::default();
+    let stick = |state: &GameProgrammeState<PadIC>| {
+        state.input_status.stick(Axis::LeftStickX, Axis::LeftStickY)
+    };
+    poll(
+        &mut state,
+        tilted(&[(Axis::LeftStickX, 0.1), (Axis::LeftStickY, -DEADZONE)]),
+    );
+    assert_eq!(stick(&state), Vec2::ZERO);
+
+    poll(
+        &mut state,
+        tilted(&[(Axis::LeftStickX, 0.6), (Axis::LeftStickY, -1.0)]),
+    );
+    let expected = Vec2::new(0.5, -1.0);
+    assert!(
+        stick(&state).abs_diff_eq(expected, 1e-5),
+        "{} isn't {expected}",
+        stick(&state)
+    );
+}
+
+#[test]
+fn axes_bound_like_buttons_press_and_release_where_they_cross() {
+    let mut state = GameProgrammeState::default();
+    let trigger = AcceptedInput::GA(Axis::RightZ, AxisDirection::Positive);
+
+    // past the deadzone but not far enough once it's taken off
+    poll(&mut state, tilted(&[(Axis::RightZ, 0.5)]));
+    assert!(!state.input_status.is_pressed(&trigger));
+
+    poll(&mut state, tilted(&[(Axis::RightZ, 0.7)]));
+    assert!(state.input_status.just_pressed(&trigger));
+    assert!(state.input_status.is_pressed(&trigger));
+
+    // further in is the same press
+    poll(&mut state, tilted(&[(Axis::RightZ, 0.9)]));
+    assert!(!state.input_status.just_pressed(&trigger));
+    assert!(state.input_status.is_pressed(&trigger));
+
+    poll(&mut state, tilted(&[(Axis::RightZ, 0.3)]));
+    assert!(state.input_status.just_released(&trigger));
+    assert!(!state.input_status.is_pressed(&trigger));
+
+    // a stick pushed one way only presses that way
+    poll(&mut state, tilted(&[(Axis::LeftStickY, -0.9)]));
+    let down = AcceptedInput::GA(Axis::LeftStickY, AxisDirection::Negative);
+    let up = AcceptedInput::GA(Axis::LeftStickY, AxisDirection::Positive);
+    assert!(state.input_status.is_pressed(&down));
+    assert!(!state.input_status.is_pressed(&up));
+}
+
+#[test]
+fn pad_bindings_from_the_settings_file_resolve_to_their_actions() {
+    let mut state = GameProgrammeState::default();
+    let saved = BTreeMap::from([
+        (
+            "Jump".to_owned(),
+            BTreeMap::from([(String::new(), vec!["GB(South)".to_owned()])]),
+        ),
+        (
+            "Throttle".to_owned(),
+            BTreeMap::from([(String::new(), vec!["GA(RightZ, Positive)".to_owned()])]),
+        ),
+    ]);
+    state.keybindings.load_from(&saved);
+    let action_down = |state: &GameProgrammeState<PadIC>, action| {
+        state
+            .keybindings
+            .inputs(&action)
+            .iter()
+            .any(|input| state.input_status.is_pressed(input))
+    };
+
+    let mut pad = tilted(&[(Axis::RightZ, 1.0)]);
+    pad.press(Button::South);
+    poll(&mut state, pad);
+    assert!(action_down(&state, PadIC::Jump));
+    assert!(action_down(&state, PadIC::Throttle));
+
+    let mut pad = tilted(&[(Axis::RightZ, 0.0)]);
+    pad.release(Button::South);
+    poll(&mut state, pad);
+    assert!(!action_down(&state, PadIC::Jump));
+    assert!(!action_down(&state, PadIC::Throttle));
+}
@@ -0,0 +1,60 @@
+// conflicts between bindings, in a flat enum that's one input context and a nested one that's a
+// context per variant
+use brainworms_lib::{
+    serde::{Deserialize, Serialize},
+    theater::basement::input_handling::{AcceptedInput, InputContext, KeyBindings},
+    winit::keyboard::KeyCode,
+};
+
+#[derive(Default, Hash, Eq, PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(crate = "brainworms_lib::serde")]
+enum FlatIC {
+    Jump,
+    Crouch,
+    #[default]
+    Marker,
+}
+impl InputContext for FlatIC {}
+
+#[derive(Default, Hash, Eq, PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(crate = "brainworms_lib::serde")]
+enum NestedIC {
+    Walking(FlatIC),
+    Menu(FlatIC),
+    #[default]
+    Marker,
+}
+impl InputContext for NestedIC {
+    fn context(&self) -> impl Eq + std::hash::Hash {
+        std::mem::discriminant(self)
+    }
+}
+
+const SPACE: AcceptedInput = AcceptedInput::KB(KeyCode::Space);
+
+#[test]
+fn actions_of_a_flat_enum_sharing_a_key_conflict() {
+    let bindings = KeyBindings::from([(FlatIC::Jump, SPACE), (FlatIC::Crouch, SPACE)]);
+    assert_eq!(
+        bindings.conflicts_with(&FlatIC::Jump, &SPACE),
+        [&FlatIC::Crouch]
+    );
+    let conflicts = bindings.conflicts();
+    assert_eq!(conflicts.len(), 1);
+    let (input, mut actions) = conflicts[0].clone();
+    actions.sort_by_key(|action| format!("{action:?}"));
+    assert_eq!(input, SPACE);
+    assert_eq!(actions, [&FlatIC::Crouch, &FlatIC::Jump]);
+}
+
+#[test]
+fn nested_contexts_only_conflict_inside_themselves() {
+    let bindings = KeyBindings::from([
+        (NestedIC::Walking(FlatIC::Jump), SPACE),
+        (NestedIC::Menu(FlatIC::Jump), SPACE),
+    ]);
+    assert!(bindings.conflicts().is_empty());
+    assert!(bindings
+        .conflicts_with(&NestedIC::Walking(FlatIC::Crouch), &SPACE)
+        .contains(&&NestedIC::Walking(FlatIC::Jump)));
+}
@@ -0,0 +1,307 @@
+// a tape recorded off a headless programme and played back through it twice, the camera going
+// the same way both times, picks and all
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use brainworms_lib::{
+    egui,
+    macros::{Choral, Scenic},
+    parking_lot::Mutex,
+    parry3d::{bounding_volume::Aabb, na::Point3, shape::TriMesh},
+    rend3::Renderer,
+    serde::{Deserialize, Serialize},
+    theater::{
+        basement::{
+            cla::GameProgrammeSettings,
+            easing::Easing,
+            input_handling::{
+                recording::{InputEvent, InputFeel, InputRecorder, InputRecording},
+                AcceptedInput, HandlesInputContexts, InputContext, KeyBindings, Press,
+            },
+        },
+        play::{
+            backstage::plumbing::DefaultRoutines,
+            orchestra::Orchestra,
+            scene::{
+                camera_paths::{CameraKeyframe, CameraPathDefinition},
+                interactions::Interaction,
+                stage3d::{make_camera, picking::CollisionGroups, Colliders},
+                CamInfo, SceneDefinition, SceneImplementation,
+            },
+            Cued, Definitions, Implementations, Play,
+        },
+    },
+    tokio::runtime::Runtime,
+    uuid::Uuid,
+    winit::{
+        dpi::PhysicalSize,
+        event::{ElementState, MouseButton},
+        event_loop::EventLoopProxy,
+        keyboard::KeyCode,
+        window::Window,
+    },
+    GameProgramme, GameProgrammeData, GameProgrammeState, MyEvent,
+};
+
+/// how long every recorded frame is
+const FRAME: Duration = Duration::from_millis(10);
+/// how long the rise key has to be down before the camera starts going up
+const RISE_AFTER: Duration = Duration::from_millis(200);
+const GLIDE: Duration = Duration::from_millis(300);
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(crate = "brainworms_lib::serde")]
+struct DeskData;
+
+#[derive(Default, Hash, Eq, PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(crate = "brainworms_lib::serde")]
+enum DeskIC {
+    /// double click, off to the door
+    Glide,
+    /// long press, round the room
+    Tour,
+    /// held down, the camera goes up
+    Rise,
+    /// right click, whatever's under the mouse
+    Poke,
+    #[default]
+    Marker,
+}
+impl InputContext for DeskIC {}
+
+/// A desk, a door and a window, with no stage at all, only the walls to pick.
+#[derive(Default, Scenic, Choral)]
+#[input_context_enum(DeskIC)]
+#[user_data_struct(DeskData)]
+struct DeskScene {
+    uuid: Uuid,
+    name: String,
+    definition: Definitions,
+    implementation: Option<Implementations>,
+    /// every camera a move got to
+    arrivals: Vec<String>,
+}
+
+fn desk() -> CamInfo {
+    CamInfo::from_arr(&[0.0, 1.5, 0.0, 0.0, 0.0])
+}
+fn door() -> CamInfo {
+    CamInfo::from_arr(&[4.0, 1.5, -2.0, 0.0, 1.0])
+}
+fn window() -> CamInfo {
+    CamInfo::from_arr(&[-3.0, 2.0, 1.0, -0.2, -1.0])
+}
+/// A box around everything, whichever way the camera looks it's looking at a wall.
+fn walls() -> Colliders {
+    let (vertices, indices) = Aabb::new(Point3::splat(-50.0), Point3::splat(50.0)).to_trimesh();
+    Colliders::new(
+        HashMap::from([("walls".to_owned(), vec![TriMesh::new(vertices, indices)])]),
+        HashMap::from([("walls".to_owned(), CollisionGroups::PICKABLE)]),
+    )
+}
+fn round_the_room() -> Vec<CameraKeyframe> {
+    [
+        (0.0, door()),
+        (0.25, desk()),
+        (0.5, CamInfo::from_arr(&[2.0, 3.0, 2.0, -0.5, 2.0])),
+    ]
+    .map(|(at, cam)| CameraKeyframe {
+        at,
+        cam,
+        look_at: None,
+        easing: Easing::default(),
+    })
+    .into()
+}
+
+impl DeskScene {
+    fn define(&mut self) {
+        self.uuid = Uuid::from_u128(1);
+        self.name = "desk".to_owned();
+        self.definition = Definitions::SceneDefinition(SceneDefinition {
+            start_cam: "desk".to_owned(),
+            camera_paths: HashMap::from([(
+                "tour".to_owned(),
+                CameraPathDefinition {
+                    keyframes: round_the_room(),
+                    stage_node: None,
+                    look_at: None,
+                    looping: false,
+                },
+            )]),
+            interactions: HashMap::from([(
+                "walls".to_owned(),
+                Interaction::SwitchCamera {
+                    camera: "window".to_owned(),
+                    glide: GLIDE.as_secs_f32(),
+                },
+            )]),
+            ..Default::default()
+        });
+        let cameras = [("desk", desk()), ("door", door()), ("window", window())]
+            .map(|(name, info)| (name.to_owned(), make_camera((name.to_owned(), info))));
+        self.implementation = Some(Implementations::SceneImplementation(SceneImplementation {
+            cameras: HashMap::from(cameras),
+            colliders: Some(walls()),
+            ..Default::default()
+        }));
+    }
+    fn implement(
+        &mut self,
+        _settings: &GameProgrammeSettings,
+        _event_loop_proxy: &EventLoopProxy<MyEvent>,
+        _renderer: Arc<Renderer>,
+        _routines: Arc<DefaultRoutines>,
+        _rts: &Runtime,
+        _orchestra: Arc<Orchestra>,
+        _user_data: Arc<Mutex<DeskData>>,
+    ) {
+    }
+    fn starting_cam_info(&self) -> CamInfo {
+        desk()
+    }
+    fn implement_chorus(
+        &self,
+        _egui_ctx: egui::Context,
+        _orchestra: Arc<Orchestra>,
+        _settings: &GameProgrammeSettings,
+        _state: &mut GameProgrammeState<DeskIC>,
+        _user_data: Arc<Mutex<DeskData>>,
+    ) {
+    }
+    fn keybindings() -> KeyBindings<DeskIC> {
+        KeyBindings::from([
+            (
+                DeskIC::Glide,
+                AcceptedInput::DoubleClick(Press::M(MouseButton::Left)),
+            ),
+            (
+                DeskIC::Tour,
+                AcceptedInput::LongPress(Press::KB(KeyCode::KeyT)),
+            ),
+            (DeskIC::Rise, AcceptedInput::KB(KeyCode::KeyW)),
+            (DeskIC::Poke, AcceptedInput::M(MouseButton::Right)),
+        ])
+    }
+}
+impl Cued<DeskIC> for DeskScene {
+    fn on_camera_arrived(&mut self, _state: &mut GameProgrammeState<DeskIC>, camera: &str) {
+        self.arrivals.push(camera.to_owned());
+    }
+}
+impl HandlesInputContexts<DeskIC> for DeskScene {
+    fn handle_input_for_context(
+        &mut self,
+        _settings: &GameProgrammeSettings,
+        state: &mut GameProgrammeState<DeskIC>,
+        _window: Option<&Arc<Window>>,
+    ) {
+        let input_status = &state.input_status;
+        let keybindings = &state.keybindings;
+        let glide = Self::input_just_pressed(input_status, keybindings, &DeskIC::Glide);
+        let tour = Self::input_just_pressed(input_status, keybindings, &DeskIC::Tour);
+        let rise = Self::input_held_for(input_status, keybindings, &DeskIC::Rise, RISE_AFTER);
+        let poke = Self::input_just_pressed(input_status, keybindings, &DeskIC::Poke);
+        if glide {
+            state.move_camera_to("door", GLIDE, Easing::EaseInOut);
+        }
+        if tour {
+            state.play_camera_path("tour");
+        }
+        if rise {
+            if let Some(cur_camera) = state.cur_camera.as_mut() {
+                cur_camera.info.y += state.frame_delta.as_secs_f32();
+            }
+        }
+        if poke {
+            state.pick();
+        }
+    }
+}
+
+fn programme() -> GameProgramme<DeskScene, DeskIC, DeskData> {
+    let mut scene = DeskScene::default();
+    scene.define();
+    let id = scene.uuid;
+    let mut state = GameProgrammeState::default();
+    state.current_playable = Some(id);
+    state.cur_camera = Some(make_camera(("desk".to_owned(), desk())));
+    state.keybindings = DeskScene::keybindings();
+    state.input_status.window_size = PhysicalSize::new(800, 600);
+    GameProgramme {
+        data: GameProgrammeData {
+            timestamp_start: Instant::now(),
+            play: Play {
+                first_playable: id,
+                playables: HashMap::from([(id, scene)]),
+                ..Default::default()
+            },
+        },
+        state,
+        settings: GameProgrammeSettings::from_args(vec![]),
+        user_data: Default::default(),
+        rts: None,
+        save_migration: None,
+    }
+}
+
+/// Hold W, double click, hold T long enough for a long press, then right click the walls once the
+/// tour's over, a frame every [`FRAME`].
+fn record() -> InputRecording {
+    let key = |key_code, state| InputEvent::Button(AcceptedInput::KB(key_code), state);
+    let click = |state| InputEvent::Button(AcceptedInput::M(MouseButton::Left), state);
+    let poke = |state| InputEvent::Button(AcceptedInput::M(MouseButton::Right), state);
+    let script = HashMap::from([
+        (0, key(KeyCode::KeyW, ElementState::Pressed)),
+        (40, key(KeyCode::KeyW, ElementState::Released)),
+        (45, click(ElementState::Pressed)),
+        (47, click(ElementState::Released)),
+        (50, click(ElementState::Pressed)),
+        (52, click(ElementState::Released)),
+        (90, key(KeyCode::KeyT, ElementState::Pressed)),
+        (160, key(KeyCode::KeyT, ElementState::Released)),
+        (215, InputEvent::CursorMoved(400.0, 300.0)),
+        (220, poke(ElementState::Pressed)),
+        (222, poke(ElementState::Released)),
+    ]);
+    let mut programme = programme();
+    programme.state.recorder = Some(InputRecorder::new(InputFeel::of(&programme.settings)));
+    for frame in 0..300 {
+        if let Some(event) = script.get(&frame) {
+            programme
+                .state
+                .take_input(&programme.settings, event.clone());
+        }
+        // the recording goes as fast as it can, only the frame lengths on the tape count
+        programme.state.last_update = Instant::now().checked_sub(FRAME);
+        programme.play_frame(None);
+        programme.state.input_status.end_frame();
+    }
+    programme.state.recorder.take().unwrap().finish()
+}
+
+#[test]
+fn replays_move_the_camera_the_same_way_every_time() {
+    let recording = record();
+    let mut first = programme();
+    let mut second = programme();
+    let first_path = first.replay_headless(recording.clone());
+    let second_path = second.replay_headless(recording);
+    assert_eq!(first_path, second_path);
+
+    let y = |frame: usize| first_path[frame].as_ref().unwrap().y;
+    // held for longer than RISE_AFTER by the recording's clock, however quick the replay was
+    assert!(y(39) > y(0), "the camera didn't go up while W was held");
+    // the double click glided over to the door, and picking the walls over to the window
+    let arrivals = &first.data.play.playables[&Uuid::from_u128(1)].arrivals;
+    assert_eq!(arrivals, &["door".to_owned(), "window".to_owned()]);
+    // the long press went round the room, all the way to the end
+    let tour_end = round_the_room().pop().unwrap().cam;
+    assert_eq!(first_path[215].as_ref(), Some(&tour_end));
+    // and both replays picked their way to the same place
+    let last = first_path.last().unwrap().as_ref().unwrap();
+    assert!(last.location().abs_diff_eq(window().location(), 1e-4));
+}
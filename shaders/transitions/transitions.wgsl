@@ -0,0 +1,92 @@
+// Overlay drawn on top of the incoming playable during a scene transition.
+// The outgoing playable's last frame is bound as `outgoing`; every effect
+// decides how much of it (or of plain black) is still covering each pixel.
+
+struct TransitionUniforms {
+    progress: f32,
+    kind: u32,
+    time: f32,
+    _padding: f32,
+}
+
+@group(0) @binding(0)
+var outgoing: texture_2d<f32>;
+@group(0) @binding(1)
+var outgoing_sampler: sampler;
+@group(0) @binding(2)
+var<uniform> params: TransitionUniforms;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+// one triangle covering the whole screen
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0), 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+fn hash(p: vec2<f32>) -> f32 {
+    return fract(sin(dot(p, vec2<f32>(127.1, 311.7))) * 43758.5453);
+}
+
+const FADE_TO_BLACK: u32 = 1u;
+const CROSSFADE: u32 = 2u;
+const WIPE: u32 = 3u;
+const THERAC_DISSOLVE: u32 = 4u;
+
+const WIPE_EDGE: f32 = 0.05;
+const DISSOLVE_CELLS: vec2<f32> = vec2<f32>(96.0, 54.0);
+const DISSOLVE_GLOW: f32 = 0.06;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let p = params.progress;
+    switch params.kind {
+        case FADE_TO_BLACK: {
+            // first half darkens the outgoing frame, second half lifts the black off the incoming one
+            if p < 0.5 {
+                let old = textureSample(outgoing, outgoing_sampler, in.uv).rgb;
+                return vec4<f32>(mix(old, vec3<f32>(0.0), p * 2.0), 1.0);
+            }
+            return vec4<f32>(0.0, 0.0, 0.0, 1.0 - (p - 0.5) * 2.0);
+        }
+        case CROSSFADE: {
+            let old = textureSample(outgoing, outgoing_sampler, in.uv).rgb;
+            return vec4<f32>(old, 1.0 - p);
+        }
+        case WIPE: {
+            let old = textureSample(outgoing, outgoing_sampler, in.uv).rgb;
+            let edge = p * (1.0 + WIPE_EDGE);
+            return vec4<f32>(old, smoothstep(edge - WIPE_EDGE, edge, in.uv.x));
+        }
+        case THERAC_DISSOLVE: {
+            // tear random scanlines sideways and split the channels the further along we are
+            let row = floor(in.uv.y * DISSOLVE_CELLS.y);
+            let tear = (hash(vec2<f32>(row, floor(params.time * 24.0))) - 0.5) * 0.08 * p;
+            let uv = vec2<f32>(in.uv.x + tear, in.uv.y);
+            let split = vec2<f32>(0.01 * p, 0.0);
+            let r = textureSample(outgoing, outgoing_sampler, uv + split).r;
+            let g = textureSample(outgoing, outgoing_sampler, uv).g;
+            let b = textureSample(outgoing, outgoing_sampler, uv - split).b;
+            // blocks drop out in random order, glowing just before they go
+            let threshold = hash(floor(in.uv * DISSOLVE_CELLS));
+            if threshold < p {
+                discard;
+            }
+            if threshold < p + DISSOLVE_GLOW {
+                return vec4<f32>(0.2, 1.0, 0.35, 1.0);
+            }
+            return vec4<f32>(r, g, b, 1.0);
+        }
+        default: {
+            discard;
+        }
+    }
+    return vec4<f32>(0.0);
+}
@@ -4,14 +4,16 @@ pub mod linac_lab;
 
 use std::{
     collections::HashMap,
-    mem::{take, variant_count},
+    hash::Hash,
+    mem::{discriminant, take, variant_count},
     ops::{Deref, DerefMut},
 };
 
 use bl::{
     macros::Playable,
+    serde::{Deserialize, Serialize},
     the_great_mind_palace_of_theatrical_arts::{
-        basement::input_handling::{DebugInputContext, InputContext},
+        basement::input_handling::{DebugInputContext, InputContext, InputContextStack},
         play::{scene::Scenic, Play, Playable},
     },
     theater::basement::logging::register_logger,
@@ -21,7 +23,8 @@ use bl::{
 use brainworms_lib as bl;
 use curtain::Curtain;
 use linac_lab::{LinacLabIC, LinacLabScene};
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(crate = "brainworms_lib::serde")]
 pub struct BrainwormsData;
 use bl::into_variant::{self as into_variant, IntoVariant, VariantFrom};
 #[derive(Playable, VariantFrom)]
@@ -32,14 +35,19 @@ pub enum MyPlayables {
     Curtain(Curtain), // loading screens and menus
 }
 
-#[derive(Default, Hash, Eq, PartialEq, Debug, Copy, Clone)]
+#[derive(Default, Hash, Eq, PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(crate = "brainworms_lib::serde")]
 pub enum MyInputContexts {
     DebugInputContext(DebugInputContext),
     LinacLabIC(LinacLabIC),
     #[default]
     Pause,
 }
-impl InputContext for MyInputContexts {}
+impl InputContext for MyInputContexts {
+    fn context(&self) -> impl Eq + Hash {
+        discriminant(self)
+    }
+}
 
 pub fn define_play() -> Play<MyPlayables> {
     let mut all_playables: Vec<MyPlayables> = vec![
@@ -55,12 +63,13 @@ pub fn define_play() -> Play<MyPlayables> {
         playables.insert(p.playable_uuid(), p);
     }
 
-    //    let first_playable = playable_names["curtain"];
-    let first_playable = playable_names["LinacLab"];
+    let first_playable = playable_names["curtain"];
+    let loading_curtain = Some(playable_names["curtain"]);
     Play {
         first_playable,
         playables,
         playable_names,
+        loading_curtain,
     }
 }
 
@@ -77,7 +86,8 @@ fn main() {
     register_logger();
     let play = define_play();
     let mut the_game_programme = GameProgramme::new(play);
-    the_game_programme.state.cur_input_context =
-        MyInputContexts::DebugInputContext(DebugInputContext::Marker);
+    the_game_programme.state.input_contexts = InputContextStack::new(
+        MyInputContexts::DebugInputContext(DebugInputContext::Marker),
+    );
     the_game_programme.start(window_builder);
 }
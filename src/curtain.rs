@@ -1,20 +1,39 @@
+use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::{BrainwormsData, MyInputContexts};
 
 use bl::egui::Context;
 
 use bl::into_variant::IntoVariant;
+use bl::log::warn;
 use bl::parking_lot::Mutex;
+use bl::rend3::Renderer;
 use bl::the_great_mind_palace_of_theatrical_arts::basement::cla::GameProgrammeSettings;
-use bl::the_great_mind_palace_of_theatrical_arts::play::curtain::CurtainDefinition;
+use bl::the_great_mind_palace_of_theatrical_arts::play::scene::definitions::load_curtain_definition;
+use bl::theater::basement::easing::Easing;
+use bl::theater::play::backstage::plumbing::DefaultRoutines;
+use bl::theater::play::backstage::prompt_book::latest_save_slot;
+use bl::theater::play::backstage::pyrotechnics::transitions::{TransitionEffect, TransitionKind};
+use bl::theater::play::backstage::stagehands::StageDirection;
+use bl::theater::play::curtain::{
+    curtain_menu, implement_curtain, show_load_progress, CurtainCall,
+};
 use bl::theater::play::orchestra::Orchestra;
-use bl::theater::play::{Definitions, Implementations};
+use bl::theater::play::{Cued, Definitions, Implementations};
+use bl::tokio::runtime::Runtime;
+use bl::winit::event_loop::EventLoopProxy;
 use bl::{
     macros::{Choral, Playable},
     uuid::Uuid,
+    GameProgrammeState, MyEvent,
 };
 use brainworms_lib as bl;
+
+const CURTAIN_FILE: &str = "assets/scenes/curtain.ron";
+const QUICKSAVE_SLOT: &str = "quicksave";
+
 #[derive(Default, Choral, Playable)]
 #[input_context_enum(MyInputContexts)]
 #[user_data_struct(BrainwormsData)]
@@ -24,21 +43,74 @@ pub struct Curtain {
     pub definition: Definitions,
     pub implementation: Option<Implementations>,
 }
+impl Cued<MyInputContexts> for Curtain {
+    fn reads_file(&self, path: &Path) -> bool {
+        path == Path::new(CURTAIN_FILE)
+    }
+}
 impl Curtain {
     fn implement_chorus(
         &self,
         egui_ctx: Context,
-        orchestra: Arc<Orchestra>,
-        settings: &GameProgrammeSettings,
-        user_data: Arc<Mutex<BrainwormsData>>,
+        _orchestra: Arc<Orchestra>,
+        _settings: &GameProgrammeSettings,
+        state: &mut GameProgrammeState<MyInputContexts>,
+        _user_data: Arc<Mutex<BrainwormsData>>,
     ) {
+        if let Some(Implementations::CurtainImplementation(implementation)) = &self.implementation {
+            implementation.paint(&egui_ctx);
+        }
+        // without being on stage we're only covering for something that's loading
+        if state.current_playable == Some(self.uuid) {
+            // paused on top of something, otherwise the title menu
+            let paused = !state.playable_stack.is_empty();
+            let latest_save = latest_save_slot();
+            match curtain_menu(&egui_ctx, paused || latest_save.is_some(), paused) {
+                Some(CurtainCall::NewGame) => state.direct_with_effect(
+                    StageDirection::Replace("LinacLab".to_owned()),
+                    TransitionEffect::new(
+                        TransitionKind::FadeToBlack,
+                        Duration::from_secs(1),
+                        Easing::EaseInOut,
+                    ),
+                ),
+                Some(CurtainCall::Continue) => match latest_save {
+                    Some(slot) if !paused => state.load_game(&slot),
+                    _ => state.pop_playable(),
+                },
+                Some(CurtainCall::Save) => state.save_game(QUICKSAVE_SLOT),
+                Some(CurtainCall::Settings) => {
+                    state.settings_panel_open = !state.settings_panel_open
+                }
+                Some(CurtainCall::Quit) => state.exeunt(),
+                None => {}
+            }
+        }
+        show_load_progress(&egui_ctx);
     }
     fn define(&mut self) {
         self.name = "curtain".into();
-        self.definition = CurtainDefinition {
-            backgrounds: vec![],
-            actors: vec![],
+        // a typo in the curtain file leaves whatever was there before
+        match load_curtain_definition(CURTAIN_FILE) {
+            Ok(definition) => self.definition = definition.into_variant(),
+            Err(e) => warn!("the curtain keeps its old definition: {e}"),
         }
-        .into_variant();
+    }
+    fn implement(
+        &mut self,
+        _settings: &GameProgrammeSettings,
+        _event_loop_proxy: &EventLoopProxy<MyEvent>,
+        renderer: Arc<Renderer>,
+        _routines: Arc<DefaultRoutines>,
+        rts: &Runtime,
+        _orchestra: Arc<Orchestra>,
+        _user_data: Arc<Mutex<BrainwormsData>>,
+    ) {
+        let Definitions::CurtainDefinition(definition) = &self.definition else {
+            panic!("curtain has non-curtain definition")
+        };
+        self.implementation = Some(Implementations::CurtainImplementation(implement_curtain(
+            definition, renderer, rts,
+        )));
     }
 }
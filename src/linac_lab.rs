@@ -3,42 +3,48 @@ use bl::brainworms_arson::{parse_fireworks, Gay, KineticEffect, KineticLabel, Sh
 
 use bl::brainworms_farting_noises::TicketedAudioRequestData as TARD;
 use bl::brainworms_farting_noises::{AudioCommand, SoundGroup};
-use bl::log::{debug, info};
-use bl::nalgebra::distance;
+use bl::log::{debug, info, warn};
 use bl::nanorand::RandomGen;
 
 use bl::parking_lot::Mutex;
-use bl::parry3d::query::RayCast;
 
+use bl::gilrs::{Axis, Button};
 use bl::rend3::Renderer;
+use bl::serde::{Deserialize, Serialize};
 use bl::the_great_mind_palace_of_theatrical_arts::basement::input_handling::{
-    AcceptedInput, DebugInputContext, HandlesInputContexts, KeyBindings,
+    text_input::TextInput, AcceptedInput, DebugInputContext, HandlesInputContexts, KeyBindings,
+    WheelDirection,
 };
 use bl::the_great_mind_palace_of_theatrical_arts::play::orchestra::Orchestra;
 use bl::the_great_mind_palace_of_theatrical_arts::play::scene::actors::create_actor;
 use bl::the_great_mind_palace_of_theatrical_arts::play::scene::stage3d::{
-    get_collisions_from_camera, load_stage3d, CollisionMap,
+    camera_controllers::{ControllerInput, ControllerStage},
+    character_controller::{CharacterController, WalkingColliders},
+    load_stage3d,
+    picking::PickFilter,
 };
 use bl::tokio::runtime::Runtime;
 use bl::uuid::Uuid;
 use bl::winit::event::MouseButton;
-use bl::winit::event_loop::EventLoop;
+use bl::winit::event_loop::EventLoopProxy;
 use bl::winit::keyboard::KeyCode;
 use bl::winit::window::Window;
-use bl::{egui, glam, nalgebra, nanorand, MyEvent};
+use bl::{egui, nanorand, MyEvent};
 use brainworms_lib as bl;
 
 use brainworms_lib::{
     theater::{
-        basement::{cla::GameProgrammeSettings, text_files::read_lines},
+        basement::{cla::GameProgrammeSettings, easing::Easing, text_files::read_lines},
         play::{
             backstage::plumbing::DefaultRoutines,
             scene::{
                 actors::{ActressDefinition, AstinkSprite},
-                stage3d::{make_camera, make_ray, update_camera_rotation},
-                AstinkScene, CamInfo, SceneDefinition, SceneImplementation,
+                definitions::load_scene_definition,
+                props::create_prop,
+                stage3d::{cast_cameras, make_ray, turn_camera, update_camera_rotation},
+                AstinkScene, CamInfo, SceneImplementation,
             },
-            Definitions, Implementations,
+            Cued, Definitions, Implementations,
         },
     },
     GameProgrammeState,
@@ -47,10 +53,10 @@ use egui::Context;
 use nanorand::Rng;
 
 use std::borrow::BorrowMut;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::thread::sleep;
 use std::time::Duration;
-use std::{collections::HashMap, f32::consts::PI, sync::Arc};
+use std::{collections::HashMap, sync::Arc};
 
 use DebugInputContext as DIC;
 use MyInputContexts as MIC;
@@ -58,7 +64,8 @@ use MyInputContexts as MIC;
 use crate::{BrainwormsData, MyInputContexts};
 //use MyInputContexts::DebugInputContext as DIC;
 
-#[derive(Default, Hash, Eq, PartialEq, Debug, Copy, Clone)]
+#[derive(Default, Hash, Eq, PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(crate = "brainworms_lib::serde")]
 pub enum LinacLabIC {
     FocusObject,
     SwitchToDebug,
@@ -67,16 +74,21 @@ pub enum LinacLabIC {
     Marker,
 }
 
-//const PDP11_CAM_INFO: [f32; 5] = [-3.729838, 4.512105, -0.103016704, -0.4487015, 0.025398161];
-const VT100_CAM_INFO: [f32; 5] = [-5.0835533, 1.3423158, -2.9590514, -0.26870108, 6.278548];
-const THERAC_CAM_INFO: [f32; 5] = [-2.580962, 2.8690546, 2.878742, -0.27470315, 5.620602];
-const TOITOI_CAM_INFO: [f32; 5] = [-6.814362, 2.740766, 0.7109763, -0.17870337, 0.0073876693];
-const OVERVIEW_CAM_INFO: [f32; 5] = [-6.217338, 3.8491437, 5.883971, -0.40870047, 5.76257];
-const PDP11_WITH_MIDORI_CAM_INFO: [f32; 5] =
-    [-3.7894087, 3.8481617, 0.3033728, -0.29471007, 6.2545333];
+const LINAC_LAB_SCENE_FILE: &str = "assets/scenes/linac_lab.ron";
+const TEST_TEXT_FILE: &str = "assets/texts/PARADISE_LOST.txt";
+const TEST_MARKUP_FILE: &str = "lfs_scam/test_markup.txt";
+/// radians per second with a stick all the way over
+const STICK_LOOK_SPEED: f32 = 2.5;
+/// cameras looking at something that can be typed into
+const CONSOLES: [&str; 2] = ["vt100", "pdp11"];
+/// how many lines the operator console keeps on screen
+const CONSOLE_LINES: usize = 24;
+/// how long it takes to walk up to something, or back
+const CAMERA_GLIDE: Duration = Duration::from_millis(700);
 
 //#[add_common_playable_fields] // this is not worth the stupid RA errors
 #[derive(Default, bl::macros::Scenic, bl::macros::Choral)]
+#[input_context_enum(MyInputContexts)]
 #[user_data_struct(BrainwormsData)]
 pub struct LinacLabScene {
     pub uuid: Uuid,
@@ -88,11 +100,15 @@ pub struct LinacLabScene {
     pub test_markup: Vec<String>,
     pub parsed_test_markup: Vec<KineticLabel>,
     pub random_line_effects: Vec<KineticEffect>,
-    pub keybindings: KeyBindings<MyInputContexts>,
+    /// what's on the operator console's screen
+    pub console: Vec<String>,
+    /// what was typed into it last time, to go back through next time
+    pub console_history: Vec<String>,
 }
 
 impl LinacLabScene {
-    pub fn define(&mut self) {
+    /// What the bindings are until the player says otherwise.
+    pub fn default_keybindings() -> KeyBindings<MyInputContexts> {
         // add default debug bindings (this can probably be derived TODO)
         let mut keybindings = KeyBindings::from(
             [
@@ -113,6 +129,23 @@ impl LinacLabScene {
         for (lb, mb) in [(DebugInputContext::GrabWindow, MouseButton::Left)] {
             keybindings.insert(MIC::DebugInputContext(lb), AcceptedInput::M(mb));
         }
+        // and the same again on a gamepad
+        for (lb, gb) in [
+            (DIC::Sprint, Button::LeftThumb),
+            (DIC::LiftUp, Button::RightTrigger),
+            (DIC::Interact, Button::South),
+            (DIC::Back, Button::Start),
+            (DIC::SwitchToScene, Button::Select),
+        ] {
+            keybindings.insert(MIC::DebugInputContext(lb), AcceptedInput::GB(gb));
+        }
+        for (lb, gb) in [
+            (LinacLabIC::SwitchToDebug, Button::Select),
+            (LinacLabIC::FocusObject, Button::South),
+            (LinacLabIC::Back, Button::East),
+        ] {
+            keybindings.insert(MIC::LinacLabIC(lb), AcceptedInput::GB(gb));
+        }
         // add keyboard bindings for scene1
         #[allow(clippy::single_element_loop)]
         for (lb, kc) in [(LinacLabIC::SwitchToDebug, KeyCode::F7)] {
@@ -126,46 +159,27 @@ impl LinacLabScene {
         ] {
             keybindings.insert(MIC::LinacLabIC(lb), AcceptedInput::M(mb));
         }
-        self.keybindings = keybindings;
-        let next_to_pdp11 = glam::Mat4::from_scale_rotation_translation(
-            glam::Vec3::new(1.0, 1.0, 1.0),
-            glam::Quat::from_euler(glam::EulerRot::XYZ, 0., PI, 0.0),
-            glam::Vec3::new(-2.0586073, 1.5, -4.085335),
-        );
-        let mut rng = nanorand::tls_rng();
-        let random_bytes = [0; 16];
-        let random_bytes = random_bytes.map(|_| rng.generate::<u8>());
+        keybindings
+    }
+    pub fn define(&mut self) {
+        // keep the uuid when redefining, the play knows this scene by it
+        if self.uuid.is_nil() {
+            let mut rng = nanorand::tls_rng();
+            let random_bytes = [0; 16];
+            let random_bytes = random_bytes.map(|_| rng.generate::<u8>());
 
-        self.uuid = bl::uuid::Builder::from_random_bytes(random_bytes).into_uuid();
+            self.uuid = bl::uuid::Builder::from_random_bytes(random_bytes).into_uuid();
+        }
 
-        let midori = ActressDefinition {
-            name: "Midori".to_owned(),
-            directory: "lfs_scam/inochi2d-models".to_owned(),
-            transform: next_to_pdp11,
-            size: 5.0,
-        };
-        self.definition = Definitions::SceneDefinition(SceneDefinition {
-            stage: ("LinacLab".to_owned(), "assets/gltf_scenes".to_owned()),
-            actors: vec![midori],
-            props: vec![("fried_egg".to_owned(), "lfs_scam/props".to_owned())],
-            start_cam: "overview".to_owned(),
-            cameras: vec![
-                ("overview".to_owned(), OVERVIEW_CAM_INFO),
-                ("pdp11".to_owned(), PDP11_WITH_MIDORI_CAM_INFO),
-                ("vt100".to_owned(), VT100_CAM_INFO),
-                ("Therac-25".to_owned(), THERAC_CAM_INFO),
-                ("PortaPotty".to_owned(), TOITOI_CAM_INFO),
-            ]
-            .iter()
-            .fold(HashMap::new(), |mut h, (k, v)| {
-                h.insert(k.to_owned(), CamInfo::from_arr(v));
-                h
-            }),
-        });
+        // a typo in the scene file leaves whatever was there before
+        match load_scene_definition(LINAC_LAB_SCENE_FILE) {
+            Ok(definition) => self.definition = Definitions::SceneDefinition(definition),
+            Err(e) => warn!("LinacLab keeps its old definition: {e}"),
+        }
         self.name = "LinacLab".to_owned();
 
         let mut rng = nanorand::tls_rng();
-        let Some((test_text, test_lines)) = (match read_lines("assets/texts/PARADISE_LOST.txt") {
+        let Some((test_text, test_lines)) = (match read_lines(TEST_TEXT_FILE) {
             Ok(test_text) => {
                 let the_body = test_text.map(|w| w.unwrap()).collect::<Vec<_>>();
                 let good_number = rng.generate_range(0..(the_body.len() - 66));
@@ -176,7 +190,7 @@ impl LinacLabScene {
         }) else {
             panic!("couldnt read text file");
         };
-        if let Ok(markup_lines) = read_lines("lfs_scam/test_markup.txt") {
+        if let Ok(markup_lines) = read_lines(TEST_MARKUP_FILE) {
             self.test_markup = markup_lines.map(Result::unwrap).collect();
         }
         if let Ok((_, parsed)) = parse_fireworks(&self.test_markup.join("")) {
@@ -195,7 +209,7 @@ impl LinacLabScene {
     pub fn implement(
         &mut self,
         settings: &GameProgrammeSettings,
-        event_loop: &EventLoop<MyEvent>,
+        event_loop_proxy: &EventLoopProxy<MyEvent>,
         renderer: Arc<Renderer>,
         _routines: Arc<DefaultRoutines>,
         rts: &Runtime,
@@ -206,16 +220,12 @@ impl LinacLabScene {
             panic!("scene has non-scene definition")
         };
 
-        let mut scene1_cameras = HashMap::new();
-
-        for (c_n, cam_info) in &definition.cameras {
-            let cam = make_camera((c_n.to_owned(), cam_info.as_arr()));
-            scene1_cameras.insert(cam.name.clone(), cam);
-        }
+        // the stage's own cameras join these once it's loaded
+        let scene1_cameras = cast_cameras(definition, &AstinkScene::Loading);
 
         let gltf_settings = settings.gltf_settings;
 
-        let event_loop_proxy = event_loop.create_proxy();
+        let stage_event_loop_proxy = event_loop_proxy.clone();
         let scene1_uuid = self.uuid;
         let scene1_stage_name = definition.stage.0.clone();
         let scene1_stage_directory = definition.stage.1.clone();
@@ -227,8 +237,9 @@ impl LinacLabScene {
         }
         let scene1_implementation = SceneImplementation {
             stage3d: scene1_stage3d,
+            colliders: None,
             actresses: HashMap::new(),
-            props: HashMap::new(), // todo!(),
+            props: HashMap::new(),
             cameras: scene1_cameras,
         };
 
@@ -242,7 +253,7 @@ impl LinacLabScene {
         } in scene1_actors
         {
             let renderer = Arc::clone(&renderer);
-            let event_loop_proxy = event_loop.create_proxy();
+            let event_loop_proxy = event_loop_proxy.clone();
             let name = name.to_owned();
             rts.spawn(async move {
                 create_actor(
@@ -257,25 +268,16 @@ impl LinacLabScene {
                 .await
             });
         }
-        let mut collider_ids = HashMap::new();
-        [
-            "Therac-25",
-            "PortaPotty",
-            "vt100",
-            "pdp11",
-            "Podloga",
-            "Przedzialek",
-            "Sciana1",
-            "Sciana2",
-            "Sciana3",
-            "Sciana4",
-        ]
-        .iter()
-        .for_each(|c| {
-            let k = (*c).to_owned();
-            let v = k.clone();
-            collider_ids.insert(k, v.to_owned());
-        });
+        for prop in definition.props.clone() {
+            rts.spawn(create_prop(
+                prop,
+                Arc::clone(&renderer),
+                gltf_settings,
+                event_loop_proxy.clone(),
+                scene1_uuid,
+            ));
+        }
+        let collider_ids = definition.collider_ids();
         rts.spawn(async move {
             load_stage3d(
                 scene1_stage_name,
@@ -283,7 +285,7 @@ impl LinacLabScene {
                 scene1_uuid,
                 renderer,
                 gltf_settings,
-                event_loop_proxy,
+                stage_event_loop_proxy,
                 collider_ids,
             )
             .await;
@@ -314,10 +316,10 @@ impl LinacLabScene {
                 sleep(Duration::from_secs(2));
             }
             println!("sending command from shitty closure to play");
-            orchestra_player.send_cmd(AudioCommand::Play(TARD::Targeted(
-                fname.to_string(),
-                test_uuid,
-            )));
+            orchestra_player.send_cmd_for(
+                scene1_uuid,
+                AudioCommand::Play(TARD::Targeted(fname.to_string(), test_uuid)),
+            );
             let (lock, cvar) = &*cv_playback_started_send;
             let mut playback_started = lock.lock();
             *playback_started = true;
@@ -382,11 +384,61 @@ impl LinacLabScene {
         let Definitions::SceneDefinition(definition) = &self.definition else {
             panic!("scene has non-scene definition")
         };
+        // one that comes with the stage isn't known until it loads
         definition
             .cameras
             .get(&definition.start_cam)
-            .unwrap()
-            .clone()
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Answer a line typed into the operator console.
+    fn operate_console(&mut self, command: &str) {
+        self.console.push(format!("> {command}"));
+        let reply = match command.trim().to_uppercase().as_str() {
+            "" => vec![],
+            "HELP" => vec!["MODE X | MODE E | TREAT | RESET | CLEAR".to_owned()],
+            "MODE X" => vec!["BEAM TYPE: X   ENERGY (MEV): 25".to_owned()],
+            "MODE E" => vec!["BEAM TYPE: E   ENERGY (MEV): 10".to_owned()],
+            "TREAT" => vec!["MALFUNCTION 54".to_owned(), "TREATMENT PAUSE".to_owned()],
+            "RESET" => vec!["SYSTEM RESET".to_owned()],
+            "CLEAR" => {
+                self.console.clear();
+                vec![]
+            }
+            _ => vec![format!("?UNKNOWN COMMAND {command}")],
+        };
+        self.console.extend(reply);
+        let overflow = self.console.len().saturating_sub(CONSOLE_LINES);
+        self.console.drain(..overflow);
+    }
+
+    /// Give the keyboard back, keeping what was typed for next time.
+    fn stop_console(&mut self, state: &mut GameProgrammeState<MyInputContexts>) {
+        if let Some(text_input) = state.stop_typing() {
+            self.console_history = text_input.history().cloned().collect();
+        }
+    }
+
+    /// The console's screen, with the line being typed at the bottom while it's being typed into.
+    fn show_console(&self, egui_ctx: &Context, text_input: Option<&TextInput>) {
+        let Some(text_input) = text_input else {
+            return;
+        };
+        egui::Window::new("operator console").show(egui_ctx, |ui| {
+            for line in &self.console {
+                ui.monospace(line);
+            }
+            let mut line: String = text_input.line().to_owned();
+            let at = line
+                .char_indices()
+                .nth(text_input.cursor())
+                .map_or(line.len(), |(i, _)| i);
+            // still being composed, goes where it'll end up
+            let composing = text_input.preedit().map_or("", |(text, _)| text.as_str());
+            line.insert_str(at, &format!("{composing}_"));
+            ui.monospace(format!("> {line}"));
+        });
     }
 
     pub fn implement_chorus(
@@ -394,8 +446,10 @@ impl LinacLabScene {
         egui_ctx: Context,
         _orchestra: Arc<Orchestra>,
         settings: &GameProgrammeSettings,
+        state: &mut GameProgrammeState<MyInputContexts>,
         user_data: Arc<Mutex<BrainwormsData>>,
     ) {
+        self.show_console(&egui_ctx, state.text_input.as_ref());
         egui::Window::new("egui widget testing").show(&egui_ctx, |ui| {
             //
             ui.horizontal(|ui| {
@@ -422,45 +476,88 @@ impl LinacLabScene {
         });
     }
 }
+impl Cued<MyInputContexts> for LinacLabScene {
+    fn reads_file(&self, path: &Path) -> bool {
+        [LINAC_LAB_SCENE_FILE, TEST_TEXT_FILE, TEST_MARKUP_FILE]
+            .iter()
+            .any(|file| path == Path::new(file))
+    }
+    fn on_enter(&mut self, state: &mut GameProgrammeState<MyInputContexts>) {
+        state.keybindings.bind_defaults(Self::default_keybindings());
+    }
+    fn on_exit(&mut self, state: &mut GameProgrammeState<MyInputContexts>) {
+        self.stop_console(state);
+        // don't leave the next playable with a captured cursor
+        if let (Some(grabber), Some(window)) = (state.grabber.as_mut(), state.window.as_ref()) {
+            grabber.request_ungrab(window);
+        }
+    }
+    fn on_camera_arrived(&mut self, state: &mut GameProgrammeState<MyInputContexts>, camera: &str) {
+        if !CONSOLES.contains(&camera) {
+            self.stop_console(state);
+        } else if !state.is_typing() {
+            let history = self.console_history.clone();
+            state.start_typing(TextInput::with_history(history));
+        }
+    }
+}
 impl HandlesInputContexts<MyInputContexts> for LinacLabScene {
     fn handle_input_for_context(
         &mut self,
         settings: &GameProgrammeSettings,
         state: &mut GameProgrammeState<MyInputContexts>,
-        window: &Arc<Window>,
+        window: Option<&Arc<Window>>,
     ) {
+        if let Some(text_input) = state.text_input.as_mut() {
+            while let Some(command) = text_input.take_submitted() {
+                self.operate_console(&command);
+            }
+            if text_input.cancelled {
+                self.stop_console(state);
+            }
+        }
         update_camera_rotation(state);
-        let cur_camera = state.cur_camera.as_mut().unwrap();
+        let move_stick = state.input_status.stick(Axis::LeftStickX, Axis::LeftStickY);
+        let look_stick = state
+            .input_status
+            .stick(Axis::RightStickX, Axis::RightStickY);
+        let Some(cur_camera) = state.cur_camera.as_mut() else {
+            // still waiting for the stage to bring the start camera
+            return;
+        };
         let rotation = cur_camera.rotation;
         let forward = -rotation.z_axis;
         let up = rotation.y_axis;
         let side = -rotation.x_axis;
-        let buttons = &mut state.input_status.buttons;
+        let input_status = &state.input_status;
+        let keybindings = &state.keybindings;
         let cur_context = &state.cur_input_context;
-        let mouse_physical_poz = &state.input_status.mouse_physical_poz;
-        let handedness = settings.handedness;
-        let really_pressed =
-            |binding| Self::input_down(buttons, &self.keybindings, &binding).is_some_and(|k| k);
-        let really_released =
-            |binding| Self::input_up(buttons, &self.keybindings, &binding).is_some_and(|k| k);
-        let interacted_with = |binding| really_pressed(binding) || really_released(binding);
+        let really_pressed = |binding| {
+            Self::input_down(&input_status.buttons, keybindings, &binding).is_some_and(|k| k)
+        };
+        let just_released =
+            |binding| Self::input_just_released(input_status, keybindings, &binding);
+        let interacted_with = |binding| really_pressed(binding) || just_released(binding);
         let wown: fn(LinacLabIC) -> MIC = MIC::LinacLabIC; // RA thinks this is an unused variable without the signature..
         let wdbg: fn(DebugInputContext) -> MIC = MIC::DebugInputContext;
 
-        let (win_w, win_h) = window.inner_size().into();
+        let (win_w, win_h) = state.input_status.window_size.into();
+        let mut bring_up_menu = false;
+        let mut glide_to = None;
+        let mut pick = false;
+        // the debug camera flies about by itself, the scene's controller only drives the scene
+        let in_scene = matches!(state.cur_input_context, MIC::LinacLabIC(_));
 
         match cur_context {
             MyInputContexts::DebugInputContext(DIC::Marker) => {
-                if really_released(wdbg(DIC::SwitchToScene)) {
+                if just_released(wdbg(DIC::SwitchToScene)) {
                     if let Some(Implementations::SceneImplementation(ref si)) = self.implementation
                     {
                         if let Definitions::SceneDefinition(ref sd) = self.definition {
                             // reset camera to default from wherever we were in debug mode
-                            state.cur_camera = Some(si.cameras[&sd.start_cam].clone()); // ehh this should have been a reference all along tbh TODO
-                                                                                        // set input context to scene
-                            state.cur_input_context = wown(LinacLabIC::Marker);
-                            // ignore all of the other inputs since they mean something else in debug mode
-                            state.input_status.buttons.clear();
+                            state.cur_camera = si.cameras.get(&sd.start_cam).cloned(); // ehh this should have been a reference all along tbh TODO
+                                                                                       // set input context to scene
+                            state.switch_input_context(wown(LinacLabIC::Marker));
                             return;
                         }
                     }
@@ -470,27 +567,49 @@ impl HandlesInputContexts<MyInputContexts> for LinacLabScene {
                 } else {
                     settings.walk_speed
                 };
-                let mut location = cur_camera.info.location();
-                let last_update = state.last_update.unwrap();
+                let mut motion = bl::glam::Vec3A::ZERO;
+                let dt = state.frame_delta.as_secs_f32();
                 if really_pressed(wdbg(DIC::Forwards)) {
-                    location += forward * velocity * last_update.elapsed().as_secs_f32();
+                    motion += forward * velocity * dt;
                 }
                 if really_pressed(wdbg(DIC::Backwards)) {
-                    location -= forward * velocity * last_update.elapsed().as_secs_f32();
+                    motion -= forward * velocity * dt;
                 }
                 if really_pressed(wdbg(DIC::StrafeLeft)) {
-                    location += side * velocity * last_update.elapsed().as_secs_f32();
+                    motion += side * velocity * dt;
                 }
                 if really_pressed(wdbg(DIC::StrafeRight)) {
-                    location -= side * velocity * last_update.elapsed().as_secs_f32();
+                    motion -= side * velocity * dt;
                 }
                 if really_pressed(wdbg(DIC::LiftUp)) {
-                    location += up * velocity * last_update.elapsed().as_secs_f32();
+                    motion += up * velocity * dt;
                 }
+                // sticks move as far as they're pushed, and look around without grabbing anything
+                motion += (forward * move_stick.y - side * move_stick.x) * velocity * dt;
+                // flies, but not through the walls
+                let location = match (&self.definition, &self.implementation) {
+                    (
+                        Definitions::SceneDefinition(sd),
+                        Some(Implementations::SceneImplementation(SceneImplementation {
+                            colliders: Some(colliders),
+                            ..
+                        })),
+                    ) => CharacterController::default().fly(
+                        cur_camera.info.location(),
+                        motion,
+                        &WalkingColliders::of(sd, &colliders.col_map),
+                    ),
+                    _ => cur_camera.info.location() + motion,
+                };
+                turn_camera(
+                    &mut cur_camera.info,
+                    -look_stick.x * STICK_LOOK_SPEED * settings.mouse_sensitivity * dt,
+                    look_stick.y * STICK_LOOK_SPEED * settings.mouse_sensitivity * dt,
+                );
                 cur_camera
                     .info
                     .set_location(location.x, location.y, location.z);
-                if really_released(wdbg(DIC::Interact)) {
+                if just_released(wdbg(DIC::Interact)) {
                     let rayman = make_ray(
                         cur_camera,
                         &state.input_status.mouse_physical_poz,
@@ -502,134 +621,125 @@ impl HandlesInputContexts<MyInputContexts> for LinacLabScene {
                     if let Implementations::SceneImplementation(sc_imp) =
                         self.implementation.as_mut().unwrap()
                     {
-                        const MAX_TOI: f32 = 100000.0;
-                        if let AstinkScene::Loaded(stage3d) = &sc_imp.stage3d {
-                            for (c_name, colliders) in stage3d.2 .2.col_map.iter() {
-                                for c in colliders {
-                                    if let Some(toi) = c.cast_local_ray(&rayman, MAX_TOI, true) {
-                                        let intersection = rayman.point_at(toi);
-                                        let cam_point = nalgebra::Point3::from([
-                                            location.x, location.y, location.z,
-                                        ]);
-                                        if cam_point != intersection {
-                                            println!(
-                                                "{} intersects mouse ray at {}",
-                                                c_name, intersection
-                                            );
-                                            #[cfg(feature = "extra_debugging")]
-                                            {
-                                                let renderer = state.renderer.clone().unwrap();
-                                                crate::bl::theater::basement::debug_profiling_etc::draw_debug_mouse_picking_doodad(
-                                                    crate::bl::theater::basement::debug_profiling_etc::DebugPickingDoodad::TheRay,
-                                                    &cam_point,
-                                                    &intersection,
-                                                    &renderer,
-                                                    settings.handedness,
-                                                    c,
-                                                );
-                                                crate::bl::theater::basement::debug_profiling_etc::draw_debug_mouse_picking_doodad(
-                                                    crate::bl::theater::basement::debug_profiling_etc::DebugPickingDoodad::TheColliderShape,
-                                                    &cam_point,
-                                                    &intersection,
-                                                    &renderer,
-                                                    settings.handedness,
-                                                    c,
-                                                );
-                                            }
-                                        }
-                                    }
+                        if let Some(colliders) = &sc_imp.colliders {
+                            for hit in colliders.cast_ray(&rayman, &PickFilter::default()) {
+                                debug!("{} intersects mouse ray at {}", hit.collider, hit.point);
+                                #[cfg(feature = "extra_debugging")]
+                                {
+                                    let cam_point = bl::nalgebra::Point3::from([
+                                        location.x, location.y, location.z,
+                                    ]);
+                                    let c = &colliders.col_map[&hit.collider][hit.part];
+                                    let renderer = state.renderer.clone().unwrap();
+                                    crate::bl::theater::basement::debug_profiling_etc::draw_debug_mouse_picking_doodad(
+                                        crate::bl::theater::basement::debug_profiling_etc::DebugPickingDoodad::TheRay,
+                                        &cam_point,
+                                        &hit.point,
+                                        &renderer,
+                                        settings.handedness,
+                                        c,
+                                    );
+                                    crate::bl::theater::basement::debug_profiling_etc::draw_debug_mouse_picking_doodad(
+                                        crate::bl::theater::basement::debug_profiling_etc::DebugPickingDoodad::TheColliderShape,
+                                        &cam_point,
+                                        &hit.point,
+                                        &renderer,
+                                        settings.handedness,
+                                        c,
+                                    );
                                 }
                             }
                         }
                     }
                 }
 
-                if really_released(wdbg(DIC::Back)) {
-                    state.grabber.as_mut().unwrap().request_ungrab(window);
+                if just_released(wdbg(DIC::Back)) {
+                    match (state.grabber.as_mut(), window) {
+                        (Some(grabber), Some(window)) if grabber.grabbed() => {
+                            grabber.request_ungrab(window)
+                        }
+                        _ => {
+                            // second escape brings up the menu
+                            bring_up_menu = true;
+                        }
+                    }
                 }
 
                 if interacted_with(wdbg(DIC::GrabWindow)) {
-                    let grabber = state.grabber.as_mut().unwrap();
-
-                    if !grabber.grabbed() {
-                        grabber.request_grab(window);
+                    if let (Some(grabber), Some(window)) = (state.grabber.as_mut(), window) {
+                        if !grabber.grabbed() {
+                            grabber.request_grab(window);
+                        }
                     }
                 }
             }
             MyInputContexts::LinacLabIC(LinacLabIC::Marker) => {
-                if really_released(wown(LinacLabIC::SwitchToDebug)) {
+                if just_released(wown(LinacLabIC::SwitchToDebug)) {
                     // set input context to debug
-                    state.cur_input_context = wdbg(DIC::Marker);
-                    // ignore all of the other inputs since they mean something else in debug mode
-                    state.input_status.buttons.clear();
+                    state.switch_input_context(wdbg(DIC::Marker));
                     return;
                 }
-                if really_released(wown(LinacLabIC::FocusObject)) {
-                    let Implementations::SceneImplementation(sc_imp) =
-                        self.implementation.as_ref().unwrap()
-                    else {
-                        return;
-                    };
-                    let AstinkScene::Loaded(stage3d) = &sc_imp.stage3d else {
-                        return;
-                    };
-                    let col_map = &stage3d.2 .2.col_map;
-                    let collisions: CollisionMap = get_collisions_from_camera(
-                        cur_camera,
-                        mouse_physical_poz,
-                        win_w,
-                        win_h,
-                        handedness,
-                        col_map,
-                    )
-                    .iter()
-                    .filter_map(|(k, v)| {
-                        sc_imp
-                            .cameras
-                            .contains_key(k)
-                            .then_some((k.to_owned(), v.to_owned()))
-                    })
-                    .collect();
-                    debug!("collisions: {collisions:?}");
-                    let Some((closest, _)) = collisions.iter().min_by(|c1, c2| {
-                        let cam_point: nalgebra::Point3<f32> = cur_camera.info.location().into();
-                        let min_c1 =
-                            c1.1.iter()
-                                .min_by(|cd1, cd2| {
-                                    distance(cd1, &cam_point).total_cmp(&distance(cd2, &cam_point))
-                                })
-                                .unwrap();
-
-                        debug!("min_c1 {min_c1}");
-                        let min_c2 =
-                            c2.1.iter()
-                                .min_by(|cd1, cd2| {
-                                    distance(cd1, &cam_point).total_cmp(&distance(cd2, &cam_point))
-                                })
-                                .unwrap();
-                        debug!("min_c2 {min_c2}");
-                        distance(min_c1, &cam_point).total_cmp(&distance(min_c2, &cam_point))
-                    }) else {
-                        buttons.retain(|_, v| v.is_pressed());
-                        return;
-                    };
-
-                    state.cur_camera = Some(sc_imp.cameras[closest].clone());
+                if just_released(wown(LinacLabIC::FocusObject)) {
+                    // the scene's interactions say where to go from here
+                    pick = true;
                 }
-                if really_released(wown(LinacLabIC::Back)) {
-                    let Implementations::SceneImplementation(sc_imp) =
-                        self.implementation.as_ref().unwrap()
-                    else {
-                        return;
-                    };
+                if just_released(wown(LinacLabIC::Back)) {
                     let Definitions::SceneDefinition(ref sd) = self.definition else {
                         return;
                     };
-                    state.cur_camera = Some(sc_imp.cameras[&sd.start_cam].clone());
+                    glide_to = Some(sd.start_cam.clone());
                 }
             }
             _ => {}
         }
-        buttons.retain(|_, v| v.is_pressed());
+        if let (
+            true,
+            Definitions::SceneDefinition(definition),
+            Some(Implementations::SceneImplementation(sc_imp)),
+        ) = (in_scene, &self.definition, &self.implementation)
+        {
+            let zoom: f32 = [(WheelDirection::Up, 1.0), (WheelDirection::Down, -1.0)]
+                .into_iter()
+                .filter(|(direction, _)| {
+                    state
+                        .input_status
+                        .just_pressed
+                        .contains(&AcceptedInput::Wheel(*direction))
+                })
+                .map(|(_, notches)| notches)
+                .sum();
+            let dt = state.frame_delta.as_secs_f32();
+            let input = ControllerInput {
+                look: bl::glam::vec2(-look_stick.x, look_stick.y)
+                    * STICK_LOOK_SPEED
+                    * settings.mouse_sensitivity
+                    * dt,
+                movement: move_stick,
+                zoom,
+                speed: settings.walk_speed,
+            };
+            let colliders = sc_imp
+                .colliders
+                .as_ref()
+                .map(|colliders| &colliders.col_map);
+            state.drive_camera(
+                &input,
+                ControllerStage {
+                    definition,
+                    colliders,
+                },
+            );
+        }
+        if pick {
+            state.pick();
+        }
+        if let Some(camera) = glide_to {
+            // typing starts again once it gets there, if it's another console
+            self.stop_console(state);
+            state.move_camera_to(&camera, CAMERA_GLIDE, Easing::EaseInOut);
+        }
+        if bring_up_menu {
+            state.push_playable("curtain");
+        }
     }
 }